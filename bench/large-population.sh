@@ -0,0 +1,21 @@
+#!/bin/sh
+# Benchmark scenario of the "large populations" section of the readme: a million 
+# agents on a random topology of degree 10, 10 rounds. Prints the wall time and 
+# the peak resident memory (Linux only, read from /proc). 
+set -e
+cd "$(dirname "$0")/.."
+cargo build --release -q
+start=$(date +%s)
+./target/release/simulation --population reptrack:500000,random:500000 --topology random --degree 10 \
+    --rounds 10 --network-stats false > /dev/null &
+pid=$!
+peak=0
+while kill -0 $pid 2> /dev/null; do
+    hwm=$(awk '/^VmHWM/ { print $2 }' /proc/$pid/status 2> /dev/null || true)
+    if [ -n "$hwm" ]; then
+        peak=$hwm
+    fi
+    sleep 1
+done
+wait $pid
+echo "$(( $(date +%s) - start )) s, peak $(( peak / 1024 )) MiB resident"
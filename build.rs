@@ -0,0 +1,22 @@
+use std::process::Command;
+
+// makes the current git commit available to the reproducibility manifest
+fn main() {
+    let commit = Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string()); 
+    if let Some(commit) = commit {
+        let dirty = Command::new("git")
+            .args(["status", "--porcelain", "--untracked-files=no"])
+            .output()
+            .map(|output| !output.stdout.is_empty())
+            .unwrap_or(false); 
+        println!("cargo:rustc-env=GIT_COMMIT={}{}", commit, if dirty { "-dirty" } else { "" }); 
+    }
+    println!("cargo:rerun-if-changed=.git/HEAD"); 
+    println!("cargo:rerun-if-changed=.git/refs"); 
+    println!("cargo:rerun-if-changed=src"); 
+}
@@ -0,0 +1,39 @@
+use rand::rngs::StdRng;
+use rand::Rng;
+
+use crate::config::Config;
+use crate::{population, spawn, Agent};
+
+// Adds `config.arrivals` newcomers, each from a population group chosen with 
+// probability proportional to the group's initial size, carrying the group's label 
+// and dispositions, and charged the entry fee. 
+// Returns their indices. 
+pub fn arrive(agents: &mut Vec<Agent>, config: &Config, round: i32, next_id: &mut usize, rng: &mut StdRng) -> Vec<usize> {
+    let groups = population(config); 
+    let total: usize = groups.iter().map(|(_, group)| group.count).sum(); 
+    let mut newcomers = vec![]; 
+    if total == 0 {
+        return newcomers; 
+    }
+    for _ in 0..config.arrivals {
+        let mut pick = rng.gen_range(0..total); 
+        let definition = groups.iter()
+            .find(|(_, group)| {
+                if pick < group.count {
+                    true
+                } else {
+                    pick -= group.count; 
+                    false
+                }
+            })
+            .unwrap(); 
+        let id = *next_id; 
+        *next_id += 1; 
+        let mut agent = spawn(definition, id, &config.game, config.seed, config.jitter()); 
+        agent.arrived = Some(round); 
+        agent.energy -= config.entry_fee; 
+        newcomers.push(agents.len()); 
+        agents.push(agent); 
+    }
+    newcomers
+}
@@ -0,0 +1,195 @@
+use core::fmt;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use rand::rngs::StdRng;
+use rand::seq::index;
+
+use crate::history::{HistoryView, PairRecord};
+use crate::Agent;
+
+// which agents get an end-of-run autopsy
+#[derive(Clone, Copy)]
+pub enum Autopsy {
+    All, 
+    // a random sample of this many agents, alive or dead
+    Sample(usize), 
+}
+
+impl FromStr for Autopsy {
+    type Err = (); 
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "all" => Ok(Autopsy::All), 
+            _ => s.parse().map(Autopsy::Sample).map_err(|_| ())
+        }
+    }
+}
+
+impl fmt::Display for Autopsy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Autopsy::All => write!(f, "all"), 
+            Autopsy::Sample(n) => write!(f, "{}", n), 
+        }
+    }
+}
+
+// summary of an agent's energy over the rounds it lived
+#[derive(Clone, Copy)]
+pub struct Trajectory {
+    start: f64, 
+    peak: (f64, i32), 
+    low: (f64, i32), 
+}
+
+impl Trajectory {
+    pub fn observe(trajectory: &mut Option<Trajectory>, energy: f64, round: i32) {
+        match trajectory {
+            Some(t) => {
+                if energy > t.peak.0 {
+                    t.peak = (energy, round); 
+                }
+                if energy < t.low.0 {
+                    t.low = (energy, round); 
+                }
+            }
+            None => *trajectory = Some(Trajectory { start: energy, peak: (energy, round), low: (energy, round) }), 
+        }
+    }
+}
+
+// agents removed during the run, with the round they died in
+#[derive(Default)]
+pub struct Graveyard {
+    dead: Vec<(Agent, i32)>, 
+}
+
+impl Graveyard {
+    // removes the agents that don't survive the round, keeping them for the autopsy
+    pub fn bury(&mut self, agents: &mut Vec<Agent>, round: i32, alive: impl Fn(&Agent) -> bool) {
+        let (living, dead): (Vec<Agent>, Vec<Agent>) = std::mem::take(agents).into_iter().partition(|agent| alive(agent)); 
+        *agents = living; 
+        self.dead.extend(dead.into_iter().map(|agent| (agent, round))); 
+    }
+
+    // Prints per agent its energy trajectory, loans given and taken, how often it 
+    // was exploited, its top partners and the final state of its strategy. 
+    pub fn report(&self, agents: &[Agent], autopsy: Autopsy, history: &HistoryView, rng: &mut StdRng) {
+        let mut all: Vec<(&Agent, Option<i32>)> = agents.iter().map(|agent| (agent, None)).collect(); 
+        all.extend(self.dead.iter().map(|(agent, round)| (agent, Some(*round)))); 
+        all.sort_by_key(|(agent, _)| agent.id); 
+        let chosen: Vec<usize> = match autopsy {
+            Autopsy::All => (0..all.len()).collect(), 
+            Autopsy::Sample(n) => {
+                let mut chosen = index::sample(rng, all.len(), n.min(all.len())).into_vec(); 
+                chosen.sort_unstable(); 
+                chosen
+            }
+        };
+        for i in chosen {
+            let (agent, died) = all[i]; 
+            examine(agent, died, history); 
+        }
+    }
+}
+
+fn examine(agent: &Agent, died: Option<i32>, history: &HistoryView) {
+    match died {
+        Some(round) => println!("autopsy of agent {} ({}), died in round {}:", agent.id, agent.kind(), round), 
+        None => println!("autopsy of agent {} ({}), alive:", agent.id, agent.kind()), 
+    }
+    if let Some(t) = agent.trajectory {
+        println!(
+            " - energy: started with {:.2}, peak {:.2} in round {}, low {:.2} in round {}, final {:.2}", 
+            t.start, t.peak.0, t.peak.1, t.low.0, t.low.1, agent.energy
+        ); 
+    }
+
+    let partners = history.partners(agent.id); 
+    let sum = |records: &mut dyn Iterator<Item = PairRecord>| {
+        records.fold(PairRecord::default(), |total, r| PairRecord {
+            cooperations: total.cooperations + r.cooperations, 
+            defections: total.defections + r.defections, 
+            rejections: total.rejections + r.rejections, 
+        })
+    };
+    let lent = sum(&mut partners.values().map(|(lent, _)| *lent)); 
+    let borrowed = sum(&mut partners.values().map(|(_, borrowed)| *borrowed)); 
+    println!(
+        " - lent {} devices ({} returned, {} stolen, exploited for {:.1} energy), rejected {} requests", 
+        lent.cooperations + lent.defections, 
+        lent.cooperations, 
+        lent.defections, 
+        agent.lost_to_defections, 
+        lent.rejections
+    ); 
+    println!(
+        " - borrowed {} devices ({} returned, {} kept), rejected {} times", 
+        borrowed.cooperations + borrowed.defections, 
+        borrowed.cooperations, 
+        borrowed.defections, 
+        borrowed.rejections
+    ); 
+
+    let mut top: Vec<(usize, u32, &(PairRecord, PairRecord))> = partners.iter()
+        .map(|(partner, records)| {
+            let (lent, borrowed) = records; 
+            let exchanges = lent.cooperations + lent.defections + borrowed.cooperations + borrowed.defections; 
+            (*partner, exchanges, records)
+        })
+        .filter(|(_, exchanges, _)| *exchanges > 0)
+        .collect(); 
+    top.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0))); 
+    let top: Vec<String> = top.iter()
+        .take(TOP_PARTNERS)
+        .map(|(partner, exchanges, (lent, _))| {
+            format!("{} ({} exchanges, stole {} of its devices)", partner, exchanges, lent.defections)
+        })
+        .collect(); 
+    println!(" - top partners: {}", if top.is_empty() { "none".into() } else { top.join(", ") }); 
+
+    // the reputation table is summarized below
+    print!(" - state: {}", agent.strategy.inspect().without("reputations")); 
+    if let Some(reputations) = agent.strategy.reputations().filter(|r| !r.is_empty()) {
+        let values: Vec<f64> = reputations.values().copied().collect(); 
+        print!(
+            "; {} reputations, mean {:.2}, min {:.2}, max {:.2}", 
+            values.len(), 
+            values.iter().sum::<f64>() / values.len() as f64, 
+            values.iter().copied().fold(f64::INFINITY, f64::min), 
+            values.iter().copied().fold(f64::NEG_INFINITY, f64::max)
+        ); 
+    }
+    println!(); 
+}
+
+const TOP_PARTNERS: usize = 3; 
+
+pub type Partners = HashMap<usize, (PairRecord, PairRecord)>;
+
+#[cfg(test)]
+mod tests {
+    use super::*; 
+
+    fn trajectory(energies: &[f64]) -> Trajectory {
+        let mut trajectory = None; 
+        for (round, energy) in energies.iter().enumerate() {
+            Trajectory::observe(&mut trajectory, *energy, round as i32); 
+        }
+        trajectory.unwrap()
+    }
+
+    #[test]
+    fn the_first_observation_starts_the_trajectory() {
+        let t = trajectory(&[256.]); 
+        assert_eq!((t.start, t.peak, t.low), (256., (256., 0), (256., 0))); 
+    }
+
+    #[test]
+    fn peak_and_low_keep_the_round_they_were_first_reached() {
+        let t = trajectory(&[256., 300., 120., 300., 120., 200.]); 
+        assert_eq!((t.start, t.peak, t.low), (256., (300., 1), (120., 2))); 
+    }
+}
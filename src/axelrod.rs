@@ -0,0 +1,334 @@
+use std::collections::HashMap; 
+use std::fs; 
+use std::io::{self, Write}; 
+use std::process; 
+
+use crate::config::Config; 
+use crate::output::OutputFile; 
+use crate::{Agent, GameParams, Outcome}; 
+
+// Compatibility with the CSV conventions of the axelrod python library, to put 
+// the lending game side by side with classic iterated prisoner's dilemma 
+// tournaments. A lender cooperates (C) by lending, a borrower by returning the 
+// device; a rejected borrower is counted as cooperating. Every pair of agents is 
+// a match, every encounter between them a turn and a player's score its payout. 
+// Players are strategy types (agent kinds), so a type's figures are summed over 
+// all matches of its agents. 
+
+// move states (own move, opponent move), in axelrod's order CC, CD, DC, DD
+fn state(own: bool, opponent: bool) -> usize {
+    (!own as usize) * 2 + !opponent as usize
+}
+
+// a player's turns against one opponent
+#[derive(Default, Clone, Copy)]
+struct Cell {
+    turns: u64, 
+    score: f64, 
+    cooperations: u64, 
+    states: [u64; 4], 
+    // states that were followed by another turn, and by a cooperation
+    continued: [u64; 4], 
+    to_cooperation: [u64; 4], 
+}
+
+#[derive(Default, Clone, Copy)]
+struct Player {
+    matches: u64, 
+    initial_cooperations: u64, 
+    wins: u64, 
+}
+
+// a match between two agents, lower id first: their player indices, scores and 
+// the moves of their last turn
+struct Match {
+    players: (usize, usize), 
+    scores: (f64, f64), 
+    last: (bool, bool), 
+}
+
+#[derive(Default)]
+pub struct CrossTable {
+    names: Vec<String>, 
+    indices: HashMap<String, usize>, 
+    players: Vec<Player>, 
+    // (player, opponent) -> the player's turns
+    cells: HashMap<(usize, usize), Cell>, 
+    matches: HashMap<(usize, usize), Match>, 
+}
+
+impl CrossTable {
+    fn player(&mut self, name: &str) -> usize {
+        if let Some(&i) = self.indices.get(name) {
+            return i; 
+        }
+        self.names.push(name.to_string()); 
+        self.players.push(Player::default()); 
+        self.indices.insert(name.to_string(), self.names.len() - 1); 
+        self.names.len() - 1
+    }
+
+    fn turn(&mut self, player: usize, opponent: usize, score: f64, moves: (bool, bool), last: Option<(bool, bool)>) {
+        let cell = self.cells.entry((player, opponent)).or_default(); 
+        cell.turns += 1; 
+        cell.score += score; 
+        cell.cooperations += moves.0 as u64; 
+        cell.states[state(moves.0, moves.1)] += 1; 
+        if let Some((own, other)) = last {
+            cell.continued[state(own, other)] += 1; 
+            cell.to_cooperation[state(own, other)] += moves.0 as u64; 
+        }
+    }
+
+    // one encounter of the simulation, with its final outcome
+    pub fn record(&mut self, lender: &Agent, borrower: &Agent, outcome: Outcome, game: &GameParams) {
+        let (moves, scores) = match outcome {
+            Outcome::Rejected => ((false, true), (0., 0.)), 
+            Outcome::Cooperated => ((true, true), (game.lender_coop_payout, game.borrower_coop_payout)), 
+            Outcome::Defected => ((true, false), (game.lender_defect_payout, game.borrower_defect_payout)), 
+        }; 
+        let (l, b) = (self.player(&lender.kind()), self.player(&borrower.kind())); 
+        // the match is keyed and its moves kept lower id first
+        let flipped = lender.id > borrower.id; 
+        let key = if flipped { (borrower.id, lender.id) } else { (lender.id, borrower.id) }; 
+        let ordered = |(x, y): (bool, bool)| if flipped { (y, x) } else { (x, y) }; 
+        let last = match self.matches.get_mut(&key) {
+            Some(m) => {
+                let last = ordered(m.last); 
+                m.last = ordered(moves); 
+                m.scores.0 += if flipped { scores.1 } else { scores.0 }; 
+                m.scores.1 += if flipped { scores.0 } else { scores.1 }; 
+                Some(last)
+            }, 
+            None => {
+                self.players[l].matches += 1; 
+                self.players[b].matches += 1; 
+                self.players[l].initial_cooperations += moves.0 as u64; 
+                self.players[b].initial_cooperations += moves.1 as u64; 
+                let players = if flipped { (b, l) } else { (l, b) }; 
+                let scores = if flipped { (scores.1, scores.0) } else { scores }; 
+                self.matches.insert(key, Match { players, scores, last: ordered(moves) }); 
+                None
+            }
+        }; 
+        self.turn(l, b, scores.0, moves, last); 
+        self.turn(b, l, scores.1, (moves.1, moves.0), last.map(|(x, y)| (y, x))); 
+    }
+
+    // matches won per player: the match's higher score wins, ties are no win
+    fn wins(&self) -> Vec<u64> {
+        let mut wins: Vec<u64> = self.players.iter().map(|player| player.wins).collect(); 
+        for m in self.matches.values() {
+            if m.scores.0 > m.scores.1 {
+                wins[m.players.0] += 1; 
+            } else if m.scores.1 > m.scores.0 {
+                wins[m.players.1] += 1; 
+            }
+        }
+        wins
+    }
+
+    // all turns of a player
+    fn total(&self, player: usize) -> Cell {
+        let mut total = Cell::default(); 
+        for cell in self.cells.iter().filter(|((p, _), _)| *p == player).map(|(_, cell)| cell) {
+            total.turns += cell.turns; 
+            total.score += cell.score; 
+            total.cooperations += cell.cooperations; 
+            for s in 0..4 {
+                total.states[s] += cell.states[s]; 
+                total.continued[s] += cell.continued[s]; 
+                total.to_cooperation[s] += cell.to_cooperation[s]; 
+            }
+        }
+        total
+    }
+
+    // players by score per turn, best first, as axelrod ranks them
+    fn ranking(&self) -> Vec<usize> {
+        let per_turn: Vec<f64> = (0..self.names.len())
+            .map(|p| self.total(p))
+            .map(|total| rate(total.score, total.turns))
+            .collect(); 
+        let mut ranking: Vec<usize> = (0..self.names.len()).collect(); 
+        ranking.sort_by(|a, b| per_turn[*b].total_cmp(&per_turn[*a]).then_with(|| self.names[*a].cmp(&self.names[*b]))); 
+        ranking
+    }
+
+    // axelrod's summary (ResultSet.write_summary): one row per player. a run is a 
+    // single repetition, so the median score is the mean score per turn
+    pub fn write_summary(&self, out: &mut impl Write) -> io::Result<()> {
+        writeln!(
+            out, 
+            "Rank,Name,Median_score,Cooperation_rating,Wins,Initial_C_rate,CC_rate,CD_rate,DC_rate,DD_rate,\
+             CC_to_C_rate,CD_to_C_rate,DC_to_C_rate,DD_to_C_rate"
+        )?; 
+        let wins = self.wins(); 
+        for (rank, p) in self.ranking().into_iter().enumerate() {
+            let total = self.total(p); 
+            let player = &self.players[p]; 
+            write!(
+                out, 
+                "{},{},{},{},{},{}", 
+                rank, 
+                quoted(&self.names[p]), 
+                rate(total.score, total.turns), 
+                rate(total.cooperations as f64, total.turns), 
+                wins[p], 
+                rate(player.initial_cooperations as f64, player.matches)
+            )?; 
+            for s in 0..4 {
+                write!(out, ",{}", rate(total.states[s] as f64, total.turns))?; 
+            }
+            for s in 0..4 {
+                write!(out, ",{}", rate(total.to_cooperation[s] as f64, total.continued[s]))?; 
+            }
+            writeln!(out)?; 
+        }
+        Ok(())
+    }
+
+    // axelrod's payoff matrix (ResultSet.payoff_matrix) as a pandas cross-table: 
+    // the mean score per turn of the row player against the column player, empty 
+    // for players that never met, both in ranking order
+    pub fn write_payoff_matrix(&self, out: &mut impl Write) -> io::Result<()> {
+        let ranking = self.ranking(); 
+        for &p in ranking.iter() {
+            write!(out, ",{}", quoted(&self.names[p]))?; 
+        }
+        writeln!(out)?; 
+        for &p in ranking.iter() {
+            write!(out, "{}", quoted(&self.names[p]))?; 
+            for &o in ranking.iter() {
+                match self.cells.get(&(p, o)) {
+                    Some(cell) => write!(out, ",{}", rate(cell.score, cell.turns))?, 
+                    None => write!(out, ",")?, 
+                }
+            }
+            writeln!(out)?; 
+        }
+        Ok(())
+    }
+
+    pub fn print(&self) {
+        let wins = self.wins(); 
+        for (rank, p) in self.ranking().into_iter().enumerate() {
+            let total = self.total(p); 
+            println!(
+                " - {}: rank {}, score per turn {:.3}, cooperation rating {:.3}, {} wins in {} matches", 
+                self.names[p], 
+                rank, 
+                rate(total.score, total.turns), 
+                rate(total.cooperations as f64, total.turns), 
+                wins[p], 
+                self.players[p].matches
+            ); 
+        }
+    }
+
+    // writes the summary and the payoff matrix to the configured files
+    pub fn write(&self, config: &Config) {
+        let files: [(&Option<String>, Writer); 2] = [
+            (&config.axelrod_summary, |table, out| table.write_summary(out)), 
+            (&config.axelrod_matrix, |table, out| table.write_payoff_matrix(out)), 
+        ]; 
+        for (path, write) in files {
+            if let Some(path) = path {
+                let written = OutputFile::create(path).and_then(|mut file| write(self, &mut file)); 
+                if let Err(e) = written {
+                    eprintln!("error: cannot write {}: {}", path, e); 
+                    process::exit(1); 
+                }
+            }
+        }
+    }
+
+    // Reads the interactions file axelrod writes when a tournament is played with 
+    // a filename (one row per match and player, with a header). The columns 
+    // Player name, Opponent name, Score and Turns are required; the cooperation, 
+    // state and win counts are used if present. 
+    pub fn import(path: &str) -> Result<CrossTable, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("cannot read {}: {}", path, e))?; 
+        let mut lines = text.lines().filter(|line| !line.trim().is_empty()); 
+        let header = split(lines.next().ok_or_else(|| format!("{} is empty", path))?); 
+        let column = |name: &str| header.iter().position(|h| h == name); 
+        let required = |name: &str| column(name).ok_or_else(|| format!("{} has no '{}' column", path, name)); 
+        let (player, opponent) = (required("Player name")?, required("Opponent name")?); 
+        let (score, turns) = (required("Score")?, required("Turns")?); 
+        let optional = [
+            "Cooperation count", "Win", "Initial cooperation", 
+            "CC count", "CD count", "DC count", "DD count", 
+            "CC to C count", "CD to C count", "DC to C count", "DD to C count", 
+        ].map(column); 
+
+        let mut table = CrossTable::default(); 
+        for (n, line) in lines.enumerate() {
+            let fields = split(line); 
+            let bad = |what: &str| format!("{} line {}: invalid {}", path, n + 2, what); 
+            let field = |i: usize| fields.get(i).map(String::as_str).unwrap_or(""); 
+            let count = |i: Option<usize>| i.map_or(Ok(0), |i| flag(field(i)).ok_or_else(|| bad(&header[i]))); 
+            let (p, o) = (table.player(field(player)), table.player(field(opponent))); 
+            let turn_count: u64 = field(turns).parse().map_err(|_| bad("Turns"))?; 
+            let points: f64 = field(score).parse().map_err(|_| bad("Score"))?; 
+            let cell = table.cells.entry((p, o)).or_default(); 
+            cell.turns += turn_count; 
+            cell.score += points; 
+            cell.cooperations += count(optional[0])?; 
+            for s in 0..4 {
+                let states = count(optional[3 + s])?; 
+                cell.states[s] += states; 
+                cell.continued[s] += states; 
+                cell.to_cooperation[s] += count(optional[7 + s])?; 
+            }
+            let stats = &mut table.players[p]; 
+            stats.matches += 1; 
+            stats.wins += count(optional[1])?; 
+            stats.initial_cooperations += count(optional[2])?; 
+        }
+        Ok(table)
+    }
+}
+
+type Writer = fn(&CrossTable, &mut OutputFile) -> io::Result<()>; 
+
+fn rate(count: f64, total: u64) -> f64 {
+    if total == 0 { 0. } else { count / total as f64 }
+}
+
+// counts, and axelrod's booleans as 1 and 0
+fn flag(value: &str) -> Option<u64> {
+    match value {
+        "True" | "true" => Some(1), 
+        "False" | "false" | "" => Some(0), 
+        _ => value.parse().ok().or_else(|| value.parse::<f64>().ok().map(|v| v as u64)), 
+    }
+}
+
+fn quoted(name: &str) -> String {
+    if name.contains(',') || name.contains('"') {
+        format!("\"{}\"", name.replace('"', "\"\""))
+    } else {
+        name.to_string()
+    }
+}
+
+// the fields of a CSV line; quoted fields may contain commas and doubled quotes
+fn split(line: &str) -> Vec<String> {
+    let mut fields = vec![]; 
+    let mut field = String::new(); 
+    let mut quoted = false; 
+    let mut chars = line.chars().peekable(); 
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"'); 
+                chars.next(); 
+            }, 
+            '"' => quoted = !quoted, 
+            ',' if !quoted => fields.push(std::mem::take(&mut field)), 
+            _ => field.push(c), 
+        }
+    }
+    fields.push(field); 
+    fields
+}
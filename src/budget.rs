@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+
+use crate::config::Excess;
+use crate::matchmaking::Request;
+use crate::Agent;
+
+// Encounters every agent may take part in per round, as lender or borrower, so 
+// that hubs in graph topologies don't get unboundedly more interaction 
+// opportunities than the others. Pairings over the budget are dropped or 
+// deferred to the start of the next round's schedule. 
+pub struct EncounterBudget {
+    limit: u32, 
+    excess: Excess, 
+    // encounters per agent index this round
+    used: Vec<u32>, 
+    // (lender id, borrower id) pairings deferred to the next round
+    deferred: Vec<(usize, usize)>, 
+    pub encounters: u64, 
+    pub dropped: u64, 
+    pub postponed: u64, 
+}
+
+impl EncounterBudget {
+    pub fn new(limit: u32, excess: Excess) -> Self {
+        EncounterBudget {
+            limit, 
+            excess, 
+            used: Vec::new(), 
+            deferred: Vec::new(), 
+            encounters: 0, 
+            dropped: 0, 
+            postponed: 0, 
+        }
+    }
+
+    // Resets the budgets and returns the pairings deferred last round whose 
+    // agents are still alive, to be played before the new schedule. A deferred 
+    // pairing is only deferred once: if it doesn't fit again it is dropped. 
+    pub fn start_round(&mut self, agents: &[Agent], index: &HashMap<usize, usize>) -> Vec<Request> {
+        self.used.clear(); 
+        self.used.resize(agents.len(), 0); 
+        self.deferred.drain(..)
+            .filter_map(|(l, b)| Some(Request { deferred: true, ..Request::new(*index.get(&l)?, *index.get(&b)?) }))
+            .collect()
+    }
+
+    // Whether the pairing fits both budgets; if so it is charged to them. 
+    pub fn admit(&mut self, agents: &[Agent], request: Request) -> bool {
+        let (l, b) = (request.lender, request.borrower); 
+        if self.used[l] < self.limit && self.used[b] < self.limit {
+            self.used[l] += 1; 
+            self.used[b] += 1; 
+            self.encounters += 1; 
+            return true; 
+        }
+        match self.excess {
+            Excess::Defer if !request.deferred => {
+                self.deferred.push((agents[l].id, agents[b].id)); 
+                self.postponed += 1; 
+            }
+            _ => self.dropped += 1, 
+        }
+        false
+    }
+
+    pub fn reset_statistics(&mut self) {
+        self.encounters = 0; 
+        self.dropped = 0; 
+        self.postponed = 0; 
+    }
+
+    pub fn report(&self) {
+        println!(
+            "encounter budget ({} per agent, {}): {} encounters, {} pairings deferred, {} dropped", 
+            self.limit, 
+            self.excess, 
+            self.encounters, 
+            self.postponed, 
+            self.dropped
+        ); 
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*; 
+    use crate::RandomStrategy; 
+
+    // agents with ids 10, 11, ... and the id -> index map
+    fn agents(n: usize) -> (Vec<Agent>, HashMap<usize, usize>) {
+        let agents: Vec<Agent> = (0..n)
+            .map(|i| Agent::new(10 + i, Box::new(RandomStrategy::new(0.5, 0.5, "random".into(), 0))))
+            .collect(); 
+        let index = agents.iter().enumerate().map(|(i, agent)| (agent.id, i)).collect(); 
+        (agents, index)
+    }
+
+    #[test]
+    fn pairings_over_the_budget_are_dropped() {
+        let (agents, index) = agents(3); 
+        let mut budget = EncounterBudget::new(2, Excess::Drop); 
+        assert!(budget.start_round(&agents, &index).is_empty()); 
+        assert!(budget.admit(&agents, Request::new(0, 1))); 
+        assert!(budget.admit(&agents, Request::new(1, 2))); 
+        // agent 1 used up its budget, agents 0 and 2 didn't
+        assert!(!budget.admit(&agents, Request::new(2, 1))); 
+        assert!(budget.admit(&agents, Request::new(2, 0))); 
+        assert_eq!((budget.encounters, budget.postponed, budget.dropped), (3, 0, 1)); 
+        assert!(budget.start_round(&agents, &index).is_empty()); 
+        assert!(budget.admit(&agents, Request::new(2, 1))); 
+    }
+
+    #[test]
+    fn deferred_pairings_open_the_next_round_once() {
+        let (agents, index) = agents(2); 
+        let mut budget = EncounterBudget::new(1, Excess::Defer); 
+        budget.start_round(&agents, &index); 
+        assert!(budget.admit(&agents, Request::new(0, 1))); 
+        assert!(!budget.admit(&agents, Request::new(1, 0))); 
+        let deferred = budget.start_round(&agents, &index); 
+        assert_eq!(deferred.len(), 1); 
+        assert_eq!((deferred[0].lender, deferred[0].borrower, deferred[0].deferred), (1, 0, true)); 
+        assert!(budget.admit(&agents, Request::new(0, 1))); 
+        // not deferred a second time
+        assert!(!budget.admit(&agents, deferred[0])); 
+        assert_eq!((budget.encounters, budget.postponed, budget.dropped), (2, 1, 1)); 
+        assert!(budget.start_round(&agents, &index).is_empty()); 
+    }
+
+    #[test]
+    fn deferred_pairings_of_dead_agents_are_forgotten() {
+        let (agents, mut index) = agents(2); 
+        let mut budget = EncounterBudget::new(1, Excess::Defer); 
+        budget.start_round(&agents, &index); 
+        budget.admit(&agents, Request::new(0, 1)); 
+        budget.admit(&agents, Request::new(1, 0)); 
+        index.remove(&11); 
+        assert!(budget.start_round(&agents, &index).is_empty()); 
+    }
+}
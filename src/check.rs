@@ -0,0 +1,82 @@
+use std::collections::HashSet;
+
+use crate::{Agent, GameParams, Outcome};
+
+// partners every strategy and its copies are asked about by the clone check
+const PROBES: usize = 8; 
+// tolerance of the energy balance of an encounter
+const EPSILON: f64 = 1e-9; 
+
+// Engine invariants asserted with --check, to catch subtle engine and strategy 
+// bugs early. The run ends with an error at the first violation, which the 
+// checks return. 
+
+fn fail(round: i32, message: String) -> Result<(), String> {
+    Err(format!("check failed in round {}: {}", round, message))
+}
+
+// payoffs of an outcome to the lender and the borrower
+fn payoffs(outcome: Outcome, game: &GameParams) -> (f64, f64) {
+    match outcome {
+        Outcome::Cooperated => (game.lender_coop_payout, game.borrower_coop_payout), 
+        Outcome::Defected => (game.lender_defect_payout, game.borrower_defect_payout), 
+        Outcome::Rejected => (0., 0.), 
+    }
+}
+
+// energy is conserved in an encounter up to the payoffs of its outcome; `before` 
+// are the lender's and borrower's energies before it
+pub fn encounter(round: i32, lender: &Agent, borrower: &Agent, before: (f64, f64), outcome: Outcome, game: &GameParams) -> Result<(), String> {
+    let (lender_payoff, borrower_payoff) = payoffs(outcome, game); 
+    for (agent, before, payoff) in [(lender, before.0, lender_payoff), (borrower, before.1, borrower_payoff)] {
+        let change = agent.energy - before; 
+        if (change - payoff).abs() > EPSILON * (1. + before.abs()) {
+            return fail(round, format!(
+                "energy of agent {} changed by {} in an encounter of agent {} with agent {}, its payoff is {}", 
+                agent.id, change, lender.id, borrower.id, payoff, 
+            )); 
+        }
+    }
+    Ok(())
+}
+
+// ids are unique, reputations only refer to ids that were given out (below 
+// `next_id`) and copies of a strategy decide like it
+pub fn round(round: i32, agents: &[Agent], next_id: usize) -> Result<(), String> {
+    let mut ids = HashSet::new(); 
+    for agent in agents {
+        if !ids.insert(agent.id) {
+            return fail(round, format!("agent id {} is used twice", agent.id)); 
+        }
+    }
+    for agent in agents {
+        if let Some(reputations) = agent.strategy.reputations() {
+            if let Some(subject) = reputations.keys().find(|subject| **subject >= next_id) {
+                return fail(round, format!("agent {} has a reputation of agent {}, which never existed", agent.id, subject)); 
+            }
+        }
+        clone(round, agent, agents)?; 
+    }
+    Ok(())
+}
+
+// Two copies of the strategy are asked the same questions about a few partners 
+// and must answer alike; the original isn't asked, so the run isn't changed. 
+fn clone(round: i32, agent: &Agent, agents: &[Agent]) -> Result<(), String> {
+    let mut a = agent.strategy.clone(); 
+    let mut b = agent.strategy.clone(); 
+    if a.reputations() != agent.strategy.reputations() {
+        return fail(round, format!("a copy of agent {}'s strategy has other reputations", agent.id)); 
+    }
+    let partners = agents.iter().map(|other| other.id).filter(|id| *id != agent.id).take(PROBES); 
+    for partner in partners {
+        if a.accept_or_reject_request(partner) != b.accept_or_reject_request(partner) 
+            || a.coop_or_defect(partner) != b.coop_or_defect(partner) {
+            return fail(round, format!(
+                "copies of agent {}'s strategy ({}) decide differently about agent {}", 
+                agent.id, agent.kind(), partner, 
+            )); 
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,169 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::Agent;
+use crate::memory;
+
+// Every positive rating one agent gave about another - praise in gossip, 
+// positive witness answers and self-reported loans - and the outcomes of all 
+// real loans. 
+#[derive(Default)]
+pub struct InteractionLog {
+    praise: HashMap<(usize, usize), u32>, 
+    // (lender, borrower) -> (cooperations, defections)
+    loans: HashMap<(usize, usize), (u32, u32)>, 
+}
+
+impl InteractionLog {
+    pub fn record_praise(&mut self, source: usize, subject: usize) {
+        *self.praise.entry((source, subject)).or_insert(0) += 1; 
+    }
+
+    pub fn record_loan(&mut self, lender: usize, borrower: usize, coop: bool) {
+        let entry = self.loans.entry((lender, borrower)).or_insert((0, 0)); 
+        if coop {
+            entry.0 += 1; 
+        } else {
+            entry.1 += 1; 
+        }
+    }
+
+    // real loans the lender granted the borrower so far
+    pub fn loans_granted(&self, lender: usize, borrower: usize) -> u32 {
+        self.loans.get(&(lender, borrower)).map_or(0, |(coops, defects)| coops + defects)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.praise.is_empty()
+    }
+
+    // approximate heap memory in bytes
+    pub fn memory(&self) -> usize {
+        memory::map_bytes::<(usize, usize), u32>(self.praise.len()) 
+            + memory::map_bytes::<(usize, usize), (u32, u32)>(self.loans.len())
+    }
+}
+
+// a group of agents that praise each other
+pub struct Ring {
+    pub members: Vec<usize>, 
+    // share of member pairs that praise each other mutually
+    pub density: f64, 
+    // share of the members' praise that is reciprocated
+    pub reciprocity: f64, 
+    // share of the praise received by members that defect more often than they 
+    // cooperate on lenders outside the cluster
+    pub contradicted: f64, 
+    pub suspicious: bool, 
+}
+
+const MIN_RING_SIZE: usize = 3; 
+const MIN_DENSITY: f64 = 0.8; 
+const MIN_RECIPROCITY_LIFT: f64 = 0.3; 
+const MIN_CONTRADICTED: f64 = 0.5; 
+
+// Looks for clusters of mutual praise among the living agents: connected 
+// components of the graph in which two agents are linked if they praised each 
+// other. A dense cluster is flagged as a likely collusion ring if its praise is 
+// far more reciprocal than the population's (baseline) or mostly contradicted 
+// by how its members behave towards outsiders. 
+// Returns the clusters and the baseline reciprocity. 
+pub fn detect(log: &InteractionLog, agents: &[Agent]) -> (Vec<Ring>, f64) {
+    let agents: HashMap<usize, &Agent> = agents.iter().map(|agent| (agent.id, agent)).collect(); 
+    let edges: HashSet<(usize, usize)> = log.praise.keys()
+        .filter(|(source, subject)| {
+            source != subject && agents.contains_key(source) && agents.contains_key(subject)
+        })
+        .copied()
+        .collect(); 
+    let reciprocated = |(source, subject): &(usize, usize)| edges.contains(&(*subject, *source)); 
+    let baseline = if edges.is_empty() {
+        0.
+    } else {
+        edges.iter().filter(|edge| reciprocated(edge)).count() as f64 / edges.len() as f64
+    };
+
+    let mut mutual: HashMap<usize, Vec<usize>> = HashMap::new(); 
+    for (source, subject) in edges.iter().filter(|edge| reciprocated(edge)) {
+        mutual.entry(*source).or_default().push(*subject); 
+    }
+
+    let mut rings = vec![]; 
+    let mut visited: HashSet<usize> = HashSet::new(); 
+    let mut nodes: Vec<&usize> = mutual.keys().collect(); 
+    nodes.sort_unstable(); 
+    for node in nodes {
+        if !visited.insert(*node) {
+            continue; 
+        }
+        let mut members = vec![*node]; 
+        let mut stack = vec![*node]; 
+        while let Some(current) = stack.pop() {
+            for next in mutual[&current].iter() {
+                if visited.insert(*next) {
+                    members.push(*next); 
+                    stack.push(*next); 
+                }
+            }
+        }
+        if members.len() < MIN_RING_SIZE {
+            continue; 
+        }
+        members.sort_unstable(); 
+        let inside: HashSet<usize> = members.iter().copied().collect(); 
+
+        let pairs = members.len() * (members.len() - 1) / 2; 
+        let mutual_pairs = members.iter().map(|m| mutual[m].len()).sum::<usize>() / 2; 
+        let outgoing: Vec<&(usize, usize)> = edges.iter().filter(|(source, _)| inside.contains(source)).collect(); 
+        let reciprocity = outgoing.iter().filter(|edge| reciprocated(edge)).count() as f64 
+            / outgoing.len() as f64; 
+        let received: Vec<&(usize, usize)> = edges.iter().filter(|(_, subject)| inside.contains(subject)).collect(); 
+        // (cooperations, defections) of every member on lenders outside the cluster
+        let mut towards_outsiders: HashMap<usize, (u32, u32)> = HashMap::new(); 
+        for ((lender, borrower), (coops, defects)) in log.loans.iter() {
+            if inside.contains(borrower) && !inside.contains(lender) {
+                let entry = towards_outsiders.entry(*borrower).or_insert((0, 0)); 
+                entry.0 += coops; 
+                entry.1 += defects; 
+            }
+        }
+        let contradicted = received.iter()
+            .filter(|(_, subject)| {
+                towards_outsiders.get(subject).is_some_and(|(coops, defects)| defects > coops)
+            })
+            .count() as f64 / received.len() as f64; 
+
+        let density = mutual_pairs as f64 / pairs as f64; 
+        let suspicious = density >= MIN_DENSITY && (
+            reciprocity - baseline >= MIN_RECIPROCITY_LIFT || contradicted >= MIN_CONTRADICTED
+        );
+        rings.push(Ring { members, density, reciprocity, contradicted, suspicious }); 
+    }
+    (rings, baseline)
+}
+
+pub fn report(log: &InteractionLog, agents: &[Agent]) {
+    let (rings, baseline) = detect(log, agents); 
+    println!("Collusion analysis (baseline praise reciprocity {:.3}):", baseline); 
+    if rings.is_empty() {
+        println!(" - no mutual praise clusters"); 
+    }
+    let types: HashMap<usize, String> = agents.iter().map(|agent| (agent.id, agent.kind())).collect(); 
+    for ring in rings.iter() {
+        let mut composition: HashMap<&String, usize> = HashMap::new(); 
+        for member in ring.members.iter() {
+            *composition.entry(&types[member]).or_insert(0) += 1; 
+        }
+        let mut composition: Vec<String> = composition.iter().map(|(st, n)| format!("{} {}", n, st)).collect(); 
+        composition.sort(); 
+        println!(
+            " - {}cluster of {} agents ({}): density {:.3}, reciprocity {:.3}, contradicted praise {:.3}", 
+            if ring.suspicious { "LIKELY COLLUSION RING: " } else { "" }, 
+            ring.members.len(), 
+            composition.join(", "), 
+            ring.density, 
+            ring.reciprocity, 
+            ring.contradicted
+        ); 
+    }
+    println!(); 
+}
@@ -0,0 +1,1148 @@
+use core::fmt;
+use std::env;
+use std::fs;
+use std::str::FromStr;
+
+use crate::autopsy::Autopsy;
+use crate::scoring::{Weights, Winner};
+use crate::{GameParams, GP, PRESETS};
+
+#[derive(Clone, Copy)]
+pub enum Command {
+    Simulate, 
+    // evolve strategy parameters with a genetic algorithm
+    Ga, 
+    // like Ga, but the device value drifts between generations with the amount of lending
+    Coevolve, 
+    // rank the strategies of the population across sampled payoff matrices
+    Meta, 
+    // sweep the trackers' forgiveness over a range
+    Forgiveness, 
+    // run many seeds and list those whose outcome matches a predicate
+    Seeds, 
+    // summarize the match results of an axelrod-python tournament
+    Axelrod, 
+    // write randomized scenario files
+    Generate, 
+    // run every scenario with every seed and compare the outcomes
+    Matrix, 
+}
+
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Command::Simulate => "simulate", 
+            Command::Ga => "ga", 
+            Command::Coevolve => "coevolve", 
+            Command::Meta => "meta", 
+            Command::Forgiveness => "forgiveness", 
+            Command::Seeds => "seeds", 
+            Command::Axelrod => "axelrod", 
+            Command::Generate => "generate", 
+            Command::Matrix => "matrix", 
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// inclusive range, given as min:max on the command line
+#[derive(Clone, Copy)]
+pub struct Range {
+    pub min: f64, 
+    pub max: f64, 
+}
+
+impl FromStr for Range {
+    type Err = (); 
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (min, max) = s.split_once(':').ok_or(())?; 
+        let min: f64 = min.parse().map_err(|_| ())?; 
+        let max: f64 = max.parse().map_err(|_| ())?; 
+        if min > max {
+            return Err(()); 
+        }
+        Ok(Range { min, max })
+    }
+}
+
+impl fmt::Display for Range {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.min, self.max)
+    }
+}
+
+// how the lender of each request is chosen
+#[derive(Clone, Copy)]
+pub enum Matchmaking {
+    AllPairs, 
+    // lenders sampled by interaction history, see matchmaking::pairs
+    Weighted, 
+}
+
+impl FromStr for Matchmaking {
+    type Err = (); 
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "all-pairs" => Ok(Matchmaking::AllPairs), 
+            "weighted" => Ok(Matchmaking::Weighted), 
+            _ => Err(())
+        }
+    }
+}
+
+impl fmt::Display for Matchmaking {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Matchmaking::AllPairs => "all-pairs", 
+            Matchmaking::Weighted => "weighted", 
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// how an energy cap is enforced, see energy_cap::CapStats::apply
+#[derive(Clone, Copy)]
+pub enum CapMode {
+    Hard, 
+    Soft, 
+}
+
+impl FromStr for CapMode {
+    type Err = (); 
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "hard" => Ok(CapMode::Hard), 
+            "soft" => Ok(CapMode::Soft), 
+            _ => Err(())
+        }
+    }
+}
+
+impl fmt::Display for CapMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            CapMode::Hard => "hard", 
+            CapMode::Soft => "soft", 
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// what happens to pairings beyond an agent's encounter budget, see 
+// budget::EncounterBudget
+#[derive(Clone, Copy)]
+pub enum Excess {
+    Drop, 
+    Defer, 
+}
+
+impl FromStr for Excess {
+    type Err = (); 
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "drop" => Ok(Excess::Drop), 
+            "defer" => Ok(Excess::Defer), 
+            _ => Err(())
+        }
+    }
+}
+
+impl fmt::Display for Excess {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Excess::Drop => "drop", 
+            Excess::Defer => "defer", 
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// what a dominating strategy type holds a share of, see stopping::StopDetector
+#[derive(Clone, Copy)]
+pub enum FixationMeasure {
+    Population, 
+    Energy, 
+}
+
+impl FromStr for FixationMeasure {
+    type Err = (); 
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "population" => Ok(FixationMeasure::Population), 
+            "energy" => Ok(FixationMeasure::Energy), 
+            _ => Err(())
+        }
+    }
+}
+
+impl fmt::Display for FixationMeasure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            FixationMeasure::Population => "population", 
+            FixationMeasure::Energy => "energy", 
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// outcome a seed search looks for, given as wins:<type>, extinct:<type> or 
+// coexistence:<rounds>: the type with the most living agents at the end, a type 
+// that died out, or at least two types alive after that many rounds. types match 
+// by substring of the agents' kind
+#[derive(Clone)]
+pub enum Predicate {
+    Wins(String), 
+    Extinct(String), 
+    Coexistence(i32), 
+}
+
+impl FromStr for Predicate {
+    type Err = (); 
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':').ok_or(())? {
+            ("wins", kind) if !kind.is_empty() => Ok(Predicate::Wins(kind.to_string())), 
+            ("extinct", kind) if !kind.is_empty() => Ok(Predicate::Extinct(kind.to_string())), 
+            ("coexistence", rounds) => rounds.parse().map(Predicate::Coexistence).map_err(|_| ()), 
+            _ => Err(()), 
+        }
+    }
+}
+
+impl fmt::Display for Predicate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Predicate::Wins(kind) => write!(f, "wins:{}", kind), 
+            Predicate::Extinct(kind) => write!(f, "extinct:{}", kind), 
+            Predicate::Coexistence(rounds) => write!(f, "coexistence:{}", rounds), 
+        }
+    }
+}
+
+// how often the round report is written: every n rounds or only at the end
+#[derive(Clone, Copy)]
+pub enum Frequency {
+    Every(i32), 
+    Final, 
+}
+
+impl FromStr for Frequency {
+    type Err = (); 
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "final" => Ok(Frequency::Final), 
+            _ => s.parse().ok().filter(|n| *n > 0).map(Frequency::Every).ok_or(())
+        }
+    }
+}
+
+impl fmt::Display for Frequency {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Frequency::Every(n) => write!(f, "{}", n), 
+            Frequency::Final => write!(f, "final"), 
+        }
+    }
+}
+
+// where the round report is written
+#[derive(Clone, Copy)]
+pub enum Sink {
+    Stdout, 
+    File, 
+    Both, 
+}
+
+impl FromStr for Sink {
+    type Err = (); 
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "stdout" => Ok(Sink::Stdout), 
+            "file" => Ok(Sink::File), 
+            "both" => Ok(Sink::Both), 
+            _ => Err(())
+        }
+    }
+}
+
+impl fmt::Display for Sink {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Sink::Stdout => "stdout", 
+            Sink::File => "file", 
+            Sink::Both => "both", 
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// how the output is meant to be read: by a person in a terminal (colors, progress 
+// bar) or by a machine, decided by whether it goes to a terminal by default
+#[derive(Clone, Copy)]
+pub enum OutputProfile {
+    Auto, 
+    Headless, 
+    Interactive, 
+}
+
+impl FromStr for OutputProfile {
+    type Err = (); 
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(OutputProfile::Auto), 
+            "headless" => Ok(OutputProfile::Headless), 
+            "interactive" => Ok(OutputProfile::Interactive), 
+            _ => Err(())
+        }
+    }
+}
+
+impl fmt::Display for OutputProfile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            OutputProfile::Auto => "auto", 
+            OutputProfile::Headless => "headless", 
+            OutputProfile::Interactive => "interactive", 
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// a population group: the preset, the number of agents, an optional label that 
+// names the group's agents in reports and exports, and overrides of the 
+// trackers' disposition towards unknown partners and acceptance threshold, and 
+// of the random strategies' probabilities towards their own group
+#[derive(Clone)]
+pub struct Group {
+    pub preset: String, 
+    pub count: usize, 
+    pub label: Option<String>, 
+    pub optimistic: Option<bool>, 
+    pub threshold: Option<f64>, 
+    pub friend_accept: Option<f32>, 
+    pub friend_coop: Option<f32>, 
+    pub drift: Option<f32>, 
+    pub patience: Option<u32>, 
+}
+
+impl Group {
+    pub fn new(preset: &str, count: usize) -> Group {
+        Group {
+            preset: preset.to_string(), 
+            count, 
+            label: None, 
+            optimistic: None, 
+            threshold: None, 
+            friend_accept: None, 
+            friend_coop: None, 
+            drift: None, 
+            patience: None, 
+        }
+    }
+}
+
+impl fmt::Display for Group {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.preset, self.count)?; 
+        if let Some(label) = &self.label {
+            write!(f, ":{}", label)?; 
+        }
+        if let Some(optimistic) = self.optimistic {
+            write!(f, ":optimistic={}", optimistic)?; 
+        }
+        if let Some(threshold) = self.threshold {
+            write!(f, ":threshold={}", threshold)?; 
+        }
+        if let Some(accept) = self.friend_accept {
+            write!(f, ":friend-accept={}", accept)?; 
+        }
+        if let Some(coop) = self.friend_coop {
+            write!(f, ":friend-coop={}", coop)?; 
+        }
+        if let Some(step) = self.drift {
+            write!(f, ":drift={}", step)?; 
+        }
+        if let Some(patience) = self.patience {
+            write!(f, ":patience={}", patience)?; 
+        }
+        Ok(())
+    }
+}
+
+// the graph agents meet on
+#[derive(Clone, Copy)]
+pub enum TopologyKind {
+    // everyone meets everyone
+    Complete, 
+    Random, 
+    ScaleFree, 
+}
+
+impl FromStr for TopologyKind {
+    type Err = (); 
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "complete" => Ok(TopologyKind::Complete), 
+            "random" => Ok(TopologyKind::Random), 
+            "scale-free" => Ok(TopologyKind::ScaleFree), 
+            _ => Err(())
+        }
+    }
+}
+
+impl fmt::Display for TopologyKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            TopologyKind::Complete => "complete", 
+            TopologyKind::Random => "random", 
+            TopologyKind::ScaleFree => "scale-free", 
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Clone)]
+pub struct Config {
+    pub command: Command, 
+    pub rounds: i32, 
+    // all randomness of a run is derived from this; drawn at random unless given
+    pub seed: u64, 
+    pub game: GameParams, 
+    pub population: Vec<Group>, 
+    // maximal uniform shift of every spawned agent's probabilities and thresholds 
+    // away from its group's values
+    pub probability_jitter: f32, 
+    pub threshold_jitter: f64, 
+    // graph agents meet on, and its mean degree
+    pub topology: TopologyKind, 
+    pub degree: usize, 
+    pub matchmaking: Matchmaking, 
+    // legacy encounter order by agent index instead of a schedule shuffled every round
+    pub fixed_order: bool, 
+    // one encounter per pair and round with random roles instead of one in each direction
+    pub symmetric_roles: bool, 
+    // whether all requests of a round are collected before lenders resolve their queues
+    pub two_phase: bool, 
+    // share of the pairs scheduled per round with all-pairs matchmaking
+    pub pair_fraction: f64, 
+    // random draws of every encounter from a stream keyed on (seed, round, lender, 
+    // borrower, earlier meetings of the pair in the round)
+    pub keyed_rng: bool, 
+    // requests per agent and round with weighted matchmaking
+    pub requests: usize, 
+    // probability for each agent and round to pass its gossip on to a random other agent
+    pub gossip_rate: f64, 
+    // rounds between the imitators' looks at their partners' performance
+    pub imitation_every: i32, 
+    // number of witnesses a lender may query about an unknown borrower, and the 
+    // energy each query costs
+    pub witnesses: usize, 
+    pub query_cost: f64, 
+    // whether rejected borrowers may present a referral from a third agent
+    pub referrals: bool, 
+    // risk aversion of adaptive strategies, see RiskAverse (0: risk neutral)
+    pub risk_aversion: f64, 
+    // factor trackers multiply their reputations with every round, and the factor 
+    // energy gains are discounted with per round when computing fitness (1: none)
+    pub discount: f64, 
+    pub fitness_discount: f64, 
+    // share of every negative reputation trackers forgive per round
+    pub forgiveness: f64, 
+    // device quality lost per returned loan (0 disables device tracking), the 
+    // multiplier for careless borrowers and the quality below which devices are replaced
+    pub depreciation: f64, 
+    pub careless_factor: f64, 
+    pub replacement_quality: f64, 
+    // whether rejected borrowers may get the loan by depositing collateral, and 
+    // the fee they pay for it
+    pub escrow: bool, 
+    pub escrow_fee: f64, 
+    // newcomers joining per round
+    pub arrivals: usize, 
+    // entry frictions: the energy newcomers pay on arrival and the loans they may 
+    // take per round during their probation
+    pub entry_fee: f64, 
+    pub probation_loans: Option<u32>, 
+    // devices any agent may borrow per round (None: unlimited)
+    pub borrow_limit: Option<u32>, 
+    // encounters an agent may take part in per round (None: unlimited) and 
+    // whether pairings beyond it are dropped or deferred to the next round
+    pub encounter_budget: Option<u32>, 
+    pub budget_excess: Excess, 
+    // energy at which an agent gives birth (None: no reproduction), and the 
+    // carrying capacity births compete for
+    pub reproduction_energy: Option<f64>, 
+    pub population_cap: Option<usize>, 
+    // maximum energy per agent (None: unbounded) and whether it is enforced fully 
+    // or only slows down growth above it
+    pub energy_cap: Option<f64>, 
+    pub cap_mode: CapMode, 
+    // energy at or below which an agent is removed; below 0 agents may run a deficit
+    pub bankruptcy: f64, 
+    // whether agents may shed their record by re-entering under a new id
+    pub whitewashing: bool, 
+    // vouching bootstrap for newcomers: how many established agents are asked to 
+    // sponsor, the energy a sponsor stakes and how long the probation lasts
+    pub vouching: bool, 
+    pub sponsor_candidates: usize, 
+    pub vouch_stake: f64, 
+    pub probation_rounds: i32, 
+    // whether borrowers may send a non-binding promise before the lender decides, 
+    // and the energy a promise of strength 1 costs
+    pub signaling: bool, 
+    pub signal_cost: f64, 
+    // whether lenders are told if they got a device lent within the last 
+    // help_memory rounds
+    pub pay_it_forward: bool, 
+    pub help_memory: i32, 
+    // whether returned devices mint karma tokens that borrowers can spend as a signal
+    pub karma: bool, 
+    // whether agents may report fake loans, and what each costs both parties
+    pub self_reports: bool, 
+    pub self_report_cost: f64, 
+    // maximum path length of the engine's transitive trust queries, 0 disables 
+    // the trust graph
+    pub trust_depth: usize, 
+    // suppresses the per-round report
+    pub quiet: bool, 
+    // whether the per-round report includes the trust network statistics, a pass 
+    // over all reputation scores
+    pub network_stats: bool, 
+    // per-round timing, printed at the end of the run
+    pub profile: bool, 
+    // asserts engine invariants after every encounter and round, see check.rs
+    pub check: bool, 
+    // whether the report shows changes since the previous report
+    pub delta_report: bool, 
+    // how often the round report is written, where to, and the file for the file sink
+    pub report_every: Frequency, 
+    pub report_sink: Sink, 
+    pub report_out: Option<String>, 
+    // colors and progress bar for terminals, or plain output for batch runs
+    pub output: OutputProfile, 
+    // agents to examine at the end of the run
+    pub autopsy: Option<Autopsy>, 
+    // how to declare the winner of a run beyond energy, and the objective weights
+    pub winner: Option<Winner>, 
+    pub score_weights: Weights, 
+    // rounds simulated before the statistics start, see Agent::reset_statistics
+    pub warm_up: i32, 
+    // stop once a single strategy type holds more than this share of the population 
+    // or of the total energy ...
+    pub fixation_share: Option<f64>, 
+    pub fixation_measure: FixationMeasure, 
+    // ... for this many consecutive rounds
+    pub fixation_rounds: usize, 
+    // stop once all per-type counts are constant and mean energies change by less 
+    // than this fraction ...
+    pub stationary_tolerance: Option<f64>, 
+    // ... over this many consecutive rounds
+    pub stationary_rounds: usize, 
+    // stop once the resident memory exceeds this many MiB
+    pub memory_budget: Option<u64>, 
+    // rounds between reports of the approximate memory use, and whether to report it at all
+    pub memory_every: Option<i32>, 
+    // csv file receiving the per-round regret of adaptive strategies
+    pub regret_out: Option<String>, 
+    // csv files receiving the run's cross-table in the axelrod-python conventions, 
+    // see axelrod::CrossTable, and the axelrod interactions file to import
+    pub axelrod_summary: Option<String>, 
+    pub axelrod_matrix: Option<String>, 
+    pub axelrod_in: Option<String>, 
+    // JSONL file receiving the inspected strategy state of sampled agents, how 
+    // many agents (0 for all) and how often
+    pub dump_out: Option<String>, 
+    pub dump_agents: usize, 
+    pub dump_every: i32, 
+    // rounds kept for the post-mortem of an abnormal end (0: none), whether with 
+    // the strategy state, and the JSONL file it is written to
+    pub snapshots: usize, 
+    pub snapshot_state: bool, 
+    pub snapshot_out: String, 
+    // file of options to apply while the run is in progress, see control::ControlFile
+    pub control_file: Option<String>, 
+    // genetic algorithm
+    pub generations: usize, 
+    pub ga_population: usize, 
+    pub mutation_rate: f64, 
+    // number of best genomes carried over unchanged and written out per generation
+    pub elite: usize, 
+    pub ga_out: Option<String>, 
+    // coevolution: the device value is multiplied by 1 + drift_rate * (target_lending - 
+    // lending rate) after every generation, i.e. scarce lending makes devices more valuable
+    pub drift_rate: f64, 
+    pub target_lending: f64, 
+    // meta-tournament: number of payoff matrices and the ranges they are sampled from
+    pub payoff_samples: usize, 
+    pub borrower_defect_range: Range, 
+    pub borrower_coop_range: Range, 
+    pub lender_defect_range: Range, 
+    pub lender_coop_range: Range, 
+    // forgiveness sweep: range, number of evenly spaced values, runs per value and 
+    // csv output
+    pub forgiveness_range: Range, 
+    pub sweep_steps: usize, 
+    pub sweep_runs: usize, 
+    pub sweep_out: Option<String>, 
+    // seed search: number of seeds tried and the outcome looked for
+    pub search_seeds: usize, 
+    pub predicate: Option<Predicate>, 
+    // scenario generator: number of files and the directory they are written to
+    pub scenarios: usize, 
+    pub scenario_dir: String, 
+    // seed matrix: scenario files (or directories of them), seeds and worker threads
+    pub matrix_scenarios: Vec<String>, 
+    pub matrix_seeds: Vec<u64>, 
+    pub threads: usize, 
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            command: Command::Simulate, 
+            rounds: 30, 
+            seed: rand::random(), 
+            game: GP, 
+            population: vec![
+                Group::new("reptrack", 64), 
+                Group::new("defector", 32), 
+                Group::new("random", 32), 
+            ], 
+            probability_jitter: 0., 
+            threshold_jitter: 0., 
+            topology: TopologyKind::Complete, 
+            degree: 6, 
+            matchmaking: Matchmaking::AllPairs, 
+            fixed_order: false, 
+            symmetric_roles: false, 
+            two_phase: false, 
+            pair_fraction: 1., 
+            keyed_rng: false, 
+            requests: 10, 
+            gossip_rate: 0., 
+            imitation_every: 10, 
+            witnesses: 0, 
+            query_cost: 0.1, 
+            referrals: false, 
+            risk_aversion: 0., 
+            discount: 1., 
+            fitness_discount: 1., 
+            forgiveness: 0., 
+            depreciation: 0., 
+            careless_factor: 3., 
+            replacement_quality: 0.2, 
+            escrow: false, 
+            escrow_fee: 0.5, 
+            arrivals: 0, 
+            entry_fee: 0., 
+            probation_loans: None, 
+            borrow_limit: None, 
+            encounter_budget: None, 
+            budget_excess: Excess::Drop, 
+            reproduction_energy: None, 
+            population_cap: None, 
+            energy_cap: None, 
+            cap_mode: CapMode::Hard, 
+            bankruptcy: 0., 
+            whitewashing: false, 
+            vouching: false, 
+            sponsor_candidates: 3, 
+            vouch_stake: 10., 
+            probation_rounds: 10, 
+            signaling: false, 
+            signal_cost: 0., 
+            pay_it_forward: false, 
+            help_memory: 3, 
+            karma: false, 
+            self_reports: false, 
+            self_report_cost: 0.1, 
+            trust_depth: 0, 
+            quiet: false, 
+            network_stats: true, 
+            profile: false, 
+            check: false, 
+            delta_report: false, 
+            report_every: Frequency::Every(1), 
+            report_sink: Sink::Stdout, 
+            report_out: None, 
+            output: OutputProfile::Auto, 
+            autopsy: None, 
+            winner: None, 
+            score_weights: Weights { energy: 1., shared: 0., partners: 0. }, 
+            fixation_share: None, 
+            warm_up: 0, 
+            fixation_measure: FixationMeasure::Population, 
+            fixation_rounds: 10, 
+            stationary_tolerance: None, 
+            stationary_rounds: 10, 
+            memory_budget: None, 
+            memory_every: None, 
+            regret_out: None, 
+            axelrod_summary: None, 
+            axelrod_matrix: None, 
+            axelrod_in: None, 
+            dump_out: None, 
+            snapshots: 0, 
+            snapshot_state: false, 
+            snapshot_out: "postmortem.jsonl".to_string(), 
+            dump_agents: 10, 
+            dump_every: 10, 
+            control_file: None, 
+            generations: 20, 
+            ga_population: 32, 
+            mutation_rate: 0.1, 
+            elite: 2, 
+            ga_out: None, 
+            drift_rate: 0.2, 
+            target_lending: 0.5, 
+            payoff_samples: 20, 
+            borrower_defect_range: Range { min: 3., max: 9. }, 
+            borrower_coop_range: Range { min: 1., max: 5. }, 
+            lender_defect_range: Range { min: -10., max: -4. }, 
+            lender_coop_range: Range { min: -2., max: 0. }, 
+            forgiveness_range: Range { min: 0., max: 0.5 }, 
+            sweep_steps: 11, 
+            sweep_runs: 5, 
+            sweep_out: None, 
+            search_seeds: 100, 
+            scenarios: 20, 
+            scenario_dir: "scenarios".to_string(), 
+            matrix_scenarios: vec![], 
+            matrix_seeds: (1..=5).collect(), 
+            threads: 1, 
+            predicate: None, 
+        }
+    }
+}
+
+impl Config {
+    pub fn from_args() -> Result<Config, String> {
+        let mut config = Config::default(); 
+        let mut args = env::args().skip(1).peekable(); 
+        if let Some(command) = args.next_if(|arg| !arg.starts_with("--")) {
+            config.command = match command.as_str() {
+                "simulate" => Command::Simulate, 
+                "ga" => Command::Ga, 
+                "coevolve" => Command::Coevolve, 
+                "meta" => Command::Meta, 
+                "forgiveness" => Command::Forgiveness, 
+                "seeds" => Command::Seeds, 
+                "axelrod" => Command::Axelrod, 
+                "generate" => Command::Generate, 
+                "matrix" => Command::Matrix, 
+                _ => return Err(format!("unknown command '{}'", command))
+            };
+        }
+        while let Some(arg) = args.next() {
+            let key = match arg.strip_prefix("--") {
+                Some(key) => key.to_string(), 
+                None => return Err(format!("unexpected argument '{}'", arg))
+            };
+            let value = args.next().ok_or(format!("missing value for '{}'", arg))?;
+            if key == "scenario" {
+                config.load_scenario(&value)?; 
+            } else {
+                config.set(&key, &value)?; 
+            }
+        }
+        config.validate()?; 
+        Ok(config)
+    }
+
+    // constraints between options, checked once all of them are set
+    fn validate(&self) -> Result<(), String> {
+        if self.warm_up < 0 || (self.warm_up > 0 && self.warm_up >= self.rounds) {
+            return Err(format!("--warm-up {} must be at least 0 and below --rounds {}", self.warm_up, self.rounds)); 
+        }
+        Ok(())
+    }
+
+    // applies a file of `--key value` lines (`#` starts a comment) as if its options 
+    // were given in place of --scenario, so that later options override them
+    pub fn load_scenario(&mut self, path: &str) -> Result<(), String> {
+        let content = fs::read_to_string(path).map_err(|e| format!("cannot read scenario {}: {}", path, e))?; 
+        for line in content.lines().map(|line| line.split('#').next().unwrap().trim()) {
+            if line.is_empty() {
+                continue; 
+            }
+            match line.split_once(char::is_whitespace) {
+                Some((key, value)) => self.set(key.trim_start_matches("--"), value.trim())
+                    .map_err(|e| format!("scenario {}: {}", path, e))?, 
+                None => return Err(format!("scenario {}: expected '--key value', got '{}'", path, line)), 
+            }
+        }
+        Ok(())
+    }
+
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        match key {
+            "rounds" => self.rounds = parse(key, value)?, 
+            "seed" => self.seed = parse(key, value)?, 
+            "population" => self.population = parse_population(value)?, 
+            "probability-jitter" => self.probability_jitter = parse(key, value)?, 
+            "threshold-jitter" => self.threshold_jitter = parse(key, value)?, 
+            "topology" => self.topology = parse(key, value)?, 
+            "degree" => self.degree = parse(key, value)?, 
+            "matchmaking" => self.matchmaking = parse(key, value)?, 
+            "fixed-order" => self.fixed_order = parse(key, value)?, 
+            "symmetric-roles" => self.symmetric_roles = parse(key, value)?, 
+            "two-phase" => self.two_phase = parse(key, value)?, 
+            "pair-fraction" => self.pair_fraction = parse_fraction(key, value)?, 
+            "keyed-rng" => self.keyed_rng = parse(key, value)?, 
+            "requests" => self.requests = parse(key, value)?, 
+            "gossip-rate" => self.gossip_rate = parse_probability(key, value)?, 
+            "imitation-every" => self.imitation_every = parse(key, value)?, 
+            "witnesses" => self.witnesses = parse(key, value)?, 
+            "query-cost" => self.query_cost = parse(key, value)?, 
+            "referrals" => self.referrals = parse(key, value)?, 
+            "risk-aversion" => self.risk_aversion = parse(key, value)?, 
+            "discount" => self.discount = parse(key, value)?, 
+            "fitness-discount" => self.fitness_discount = parse(key, value)?, 
+            "forgiveness" => self.forgiveness = parse(key, value)?, 
+            "depreciation" => self.depreciation = parse(key, value)?, 
+            "careless-factor" => self.careless_factor = parse(key, value)?, 
+            "replacement-quality" => self.replacement_quality = parse(key, value)?, 
+            "escrow" => self.escrow = parse(key, value)?, 
+            "escrow-fee" => self.escrow_fee = parse(key, value)?, 
+            "arrivals" => self.arrivals = parse(key, value)?, 
+            "entry-fee" => self.entry_fee = parse(key, value)?, 
+            "probation-loans" => self.probation_loans = Some(parse(key, value)?), 
+            "borrow-limit" => self.borrow_limit = Some(parse(key, value)?), 
+            "encounter-budget" => self.encounter_budget = Some(parse(key, value)?), 
+            "budget-excess" => self.budget_excess = parse(key, value)?, 
+            "reproduction-energy" => self.reproduction_energy = Some(parse(key, value)?), 
+            "population-cap" => self.population_cap = Some(parse(key, value)?), 
+            "energy-cap" => self.energy_cap = Some(parse(key, value)?), 
+            "cap-mode" => self.cap_mode = parse(key, value)?, 
+            "bankruptcy" => self.bankruptcy = parse(key, value)?, 
+            "whitewashing" => self.whitewashing = parse(key, value)?, 
+            "vouching" => self.vouching = parse(key, value)?, 
+            "sponsor-candidates" => self.sponsor_candidates = parse(key, value)?, 
+            "vouch-stake" => self.vouch_stake = parse(key, value)?, 
+            "probation-rounds" => self.probation_rounds = parse(key, value)?, 
+            "signaling" => self.signaling = parse(key, value)?, 
+            "signal-cost" => self.signal_cost = parse(key, value)?, 
+            "pay-it-forward" => self.pay_it_forward = parse(key, value)?, 
+            "help-memory" => self.help_memory = parse(key, value)?, 
+            "karma" => self.karma = parse(key, value)?, 
+            "self-reports" => self.self_reports = parse(key, value)?, 
+            "self-report-cost" => self.self_report_cost = parse(key, value)?, 
+            "trust-depth" => self.trust_depth = parse(key, value)?, 
+            "borrower-defect-payout" => self.game.borrower_defect_payout = parse(key, value)?, 
+            "borrower-coop-payout" => self.game.borrower_coop_payout = parse(key, value)?, 
+            "lender-defect-payout" => self.game.lender_defect_payout = parse(key, value)?, 
+            "lender-coop-payout" => self.game.lender_coop_payout = parse(key, value)?, 
+            "fixation-share" => self.fixation_share = Some(parse(key, value)?), 
+            "fixation-rounds" => self.fixation_rounds = parse(key, value)?, 
+            "fixation-measure" => self.fixation_measure = parse(key, value)?, 
+            "warm-up" => self.warm_up = parse(key, value)?, 
+            "stationary-tolerance" => self.stationary_tolerance = Some(parse(key, value)?), 
+            "stationary-rounds" => self.stationary_rounds = parse(key, value)?, 
+            "memory-budget" => self.memory_budget = Some(parse(key, value)?), 
+            "memory-every" => self.memory_every = Some(parse(key, value)?), 
+            "regret-out" => self.regret_out = Some(value.to_string()), 
+            "axelrod-summary" => self.axelrod_summary = Some(value.to_string()), 
+            "axelrod-matrix" => self.axelrod_matrix = Some(value.to_string()), 
+            "axelrod-in" => self.axelrod_in = Some(value.to_string()), 
+            "dump-out" => self.dump_out = Some(value.to_string()), 
+            "snapshots" => self.snapshots = parse(key, value)?, 
+            "snapshot-state" => self.snapshot_state = parse(key, value)?, 
+            "snapshot-out" => self.snapshot_out = value.to_string(), 
+            "dump-agents" => self.dump_agents = parse(key, value)?, 
+            "dump-every" => self.dump_every = parse(key, value)?, 
+            "control-file" => self.control_file = Some(value.to_string()), 
+            "quiet" => self.quiet = parse(key, value)?, 
+            "network-stats" => self.network_stats = parse(key, value)?, 
+            "profile" => self.profile = parse(key, value)?, 
+            "check" => self.check = parse(key, value)?, 
+            "delta-report" => self.delta_report = parse(key, value)?, 
+            "report-every" => self.report_every = parse(key, value)?, 
+            "report-sink" => self.report_sink = parse(key, value)?, 
+            "report-out" => self.report_out = Some(value.to_string()), 
+            "output" => self.output = parse(key, value)?, 
+            "autopsy" => self.autopsy = Some(parse(key, value)?), 
+            "winner" => self.winner = Some(parse(key, value)?), 
+            "score-weights" => self.score_weights = parse(key, value)?, 
+            "generations" => self.generations = parse(key, value)?, 
+            "ga-population" => self.ga_population = parse(key, value)?, 
+            "mutation-rate" => self.mutation_rate = parse_probability(key, value)?, 
+            "elite" => self.elite = parse(key, value)?, 
+            "ga-out" => self.ga_out = Some(value.to_string()), 
+            "drift-rate" => self.drift_rate = parse(key, value)?, 
+            "target-lending" => self.target_lending = parse(key, value)?, 
+            "payoff-samples" => self.payoff_samples = parse(key, value)?, 
+            "borrower-defect-range" => self.borrower_defect_range = parse(key, value)?, 
+            "borrower-coop-range" => self.borrower_coop_range = parse(key, value)?, 
+            "lender-defect-range" => self.lender_defect_range = parse(key, value)?, 
+            "lender-coop-range" => self.lender_coop_range = parse(key, value)?, 
+            "forgiveness-range" => self.forgiveness_range = parse(key, value)?, 
+            "sweep-steps" => self.sweep_steps = parse(key, value)?, 
+            "sweep-runs" => self.sweep_runs = parse(key, value)?, 
+            "sweep-out" => self.sweep_out = Some(value.to_string()), 
+            "search-seeds" => self.search_seeds = parse(key, value)?, 
+            "scenarios" => self.scenarios = parse(key, value)?, 
+            "scenario-dir" => self.scenario_dir = value.to_string(), 
+            "matrix-scenarios" => self.matrix_scenarios = value.split(',').map(str::to_string).collect(), 
+            "matrix-seeds" => self.matrix_seeds = value.split(',')
+                .map(|seed| parse(key, seed))
+                .collect::<Result<_, _>>()?, 
+            "threads" => self.threads = parse(key, value)?, 
+            "predicate" => self.predicate = Some(parse(key, value)?), 
+            _ => return Err(format!("unknown option '{}'", key))
+        }
+        Ok(())
+    }
+}
+
+impl Config {
+    // (probability, threshold) jitter of spawned agents
+    pub fn jitter(&self) -> (f32, f64) {
+        (self.probability_jitter, self.threshold_jitter)
+    }
+
+    // the resolved value of every option, in the form accepted by `set`; unset 
+    // optional values are left out
+    pub fn entries(&self) -> Vec<(&'static str, String)> {
+        let mut entries = vec![
+            ("rounds", self.rounds.to_string()), 
+            ("seed", self.seed.to_string()), 
+            ("population", self.population.iter()
+                .map(|group| group.to_string())
+                .collect::<Vec<String>>()
+                .join(",")), 
+            ("probability-jitter", self.probability_jitter.to_string()), 
+            ("threshold-jitter", self.threshold_jitter.to_string()), 
+            ("topology", self.topology.to_string()), 
+            ("degree", self.degree.to_string()), 
+            ("matchmaking", self.matchmaking.to_string()), 
+            ("fixed-order", self.fixed_order.to_string()), 
+            ("symmetric-roles", self.symmetric_roles.to_string()), 
+            ("two-phase", self.two_phase.to_string()), 
+            ("pair-fraction", self.pair_fraction.to_string()), 
+            ("keyed-rng", self.keyed_rng.to_string()), 
+            ("requests", self.requests.to_string()), 
+            ("gossip-rate", self.gossip_rate.to_string()), 
+            ("imitation-every", self.imitation_every.to_string()), 
+            ("witnesses", self.witnesses.to_string()), 
+            ("query-cost", self.query_cost.to_string()), 
+            ("referrals", self.referrals.to_string()), 
+            ("risk-aversion", self.risk_aversion.to_string()), 
+            ("discount", self.discount.to_string()), 
+            ("fitness-discount", self.fitness_discount.to_string()), 
+            ("forgiveness", self.forgiveness.to_string()), 
+            ("depreciation", self.depreciation.to_string()), 
+            ("careless-factor", self.careless_factor.to_string()), 
+            ("replacement-quality", self.replacement_quality.to_string()), 
+            ("escrow", self.escrow.to_string()), 
+            ("escrow-fee", self.escrow_fee.to_string()), 
+            ("cap-mode", self.cap_mode.to_string()), 
+            ("bankruptcy", self.bankruptcy.to_string()), 
+            ("arrivals", self.arrivals.to_string()), 
+            ("entry-fee", self.entry_fee.to_string()), 
+            ("whitewashing", self.whitewashing.to_string()), 
+            ("vouching", self.vouching.to_string()), 
+            ("sponsor-candidates", self.sponsor_candidates.to_string()), 
+            ("vouch-stake", self.vouch_stake.to_string()), 
+            ("probation-rounds", self.probation_rounds.to_string()), 
+            ("budget-excess", self.budget_excess.to_string()), 
+            ("signaling", self.signaling.to_string()), 
+            ("signal-cost", self.signal_cost.to_string()), 
+            ("pay-it-forward", self.pay_it_forward.to_string()), 
+            ("help-memory", self.help_memory.to_string()), 
+            ("karma", self.karma.to_string()), 
+            ("self-reports", self.self_reports.to_string()), 
+            ("self-report-cost", self.self_report_cost.to_string()), 
+            ("trust-depth", self.trust_depth.to_string()), 
+            ("borrower-defect-payout", self.game.borrower_defect_payout.to_string()), 
+            ("borrower-coop-payout", self.game.borrower_coop_payout.to_string()), 
+            ("lender-defect-payout", self.game.lender_defect_payout.to_string()), 
+            ("lender-coop-payout", self.game.lender_coop_payout.to_string()), 
+            ("fixation-rounds", self.fixation_rounds.to_string()), 
+            ("fixation-measure", self.fixation_measure.to_string()), 
+            ("warm-up", self.warm_up.to_string()), 
+            ("stationary-rounds", self.stationary_rounds.to_string()), 
+            ("network-stats", self.network_stats.to_string()), 
+            ("profile", self.profile.to_string()),
+            ("check", self.check.to_string()), 
+            ("dump-agents", self.dump_agents.to_string()), 
+            ("dump-every", self.dump_every.to_string()), 
+            ("snapshots", self.snapshots.to_string()), 
+            ("snapshot-state", self.snapshot_state.to_string()), 
+            ("snapshot-out", self.snapshot_out.clone()), 
+            ("delta-report", self.delta_report.to_string()), 
+            ("report-every", self.report_every.to_string()), 
+            ("report-sink", self.report_sink.to_string()), 
+            ("output", self.output.to_string()), 
+            ("quiet", self.quiet.to_string()), 
+            ("score-weights", self.score_weights.to_string()), 
+            ("generations", self.generations.to_string()), 
+            ("ga-population", self.ga_population.to_string()), 
+            ("mutation-rate", self.mutation_rate.to_string()), 
+            ("elite", self.elite.to_string()), 
+            ("drift-rate", self.drift_rate.to_string()), 
+            ("target-lending", self.target_lending.to_string()), 
+            ("payoff-samples", self.payoff_samples.to_string()), 
+            ("borrower-defect-range", self.borrower_defect_range.to_string()), 
+            ("borrower-coop-range", self.borrower_coop_range.to_string()), 
+            ("lender-defect-range", self.lender_defect_range.to_string()), 
+            ("lender-coop-range", self.lender_coop_range.to_string()), 
+            ("forgiveness-range", self.forgiveness_range.to_string()), 
+            ("sweep-steps", self.sweep_steps.to_string()), 
+            ("sweep-runs", self.sweep_runs.to_string()), 
+            ("search-seeds", self.search_seeds.to_string()), 
+            ("scenarios", self.scenarios.to_string()), 
+            ("scenario-dir", self.scenario_dir.clone()), 
+            ("matrix-seeds", self.matrix_seeds.iter()
+                .map(|seed| seed.to_string())
+                .collect::<Vec<String>>()
+                .join(",")), 
+            ("threads", self.threads.to_string()), 
+        ];
+        let optional = [
+            ("fixation-share", self.fixation_share.map(|v| v.to_string())), 
+            ("stationary-tolerance", self.stationary_tolerance.map(|v| v.to_string())), 
+            ("memory-budget", self.memory_budget.map(|v| v.to_string())), 
+            ("memory-every", self.memory_every.map(|v| v.to_string())), 
+            ("predicate", self.predicate.as_ref().map(|v| v.to_string())), 
+            ("reproduction-energy", self.reproduction_energy.map(|v| v.to_string())), 
+            ("population-cap", self.population_cap.map(|v| v.to_string())), 
+            ("energy-cap", self.energy_cap.map(|v| v.to_string())), 
+            ("probation-loans", self.probation_loans.map(|v| v.to_string())), 
+            ("borrow-limit", self.borrow_limit.map(|v| v.to_string())), 
+            ("encounter-budget", self.encounter_budget.map(|v| v.to_string())), 
+            ("autopsy", self.autopsy.map(|v| v.to_string())), 
+            ("winner", self.winner.map(|v| v.to_string())), 
+            ("regret-out", self.regret_out.clone()), 
+            ("axelrod-summary", self.axelrod_summary.clone()), 
+            ("axelrod-matrix", self.axelrod_matrix.clone()), 
+            ("axelrod-in", self.axelrod_in.clone()), 
+            ("dump-out", self.dump_out.clone()), 
+            ("control-file", self.control_file.clone()), 
+            ("report-out", self.report_out.clone()), 
+            ("ga-out", self.ga_out.clone()), 
+            ("sweep-out", self.sweep_out.clone()), 
+            ("matrix-scenarios", (!self.matrix_scenarios.is_empty()).then(|| self.matrix_scenarios.join(","))), 
+        ];
+        for (key, value) in optional {
+            if let Some(value) = value {
+                entries.push((key, value)); 
+            }
+        }
+        entries
+    }
+}
+
+fn parse<T: FromStr>(key: &str, value: &str) -> Result<T, String> {
+    value.parse::<T>().map_err(|_| format!("invalid value '{}' for '{}'", value, key))
+}
+
+// a share in (0, 1]
+fn parse_fraction(key: &str, value: &str) -> Result<f64, String> {
+    let fraction: f64 = parse(key, value)?; 
+    if fraction > 0. && fraction <= 1. {
+        Ok(fraction)
+    } else {
+        Err(format!("invalid value '{}' for '{}', expected a share in (0, 1]", value, key))
+    }
+}
+
+// a probability in [0, 1]
+fn parse_probability(key: &str, value: &str) -> Result<f64, String> {
+    let probability: f64 = parse(key, value)?; 
+    if (0. ..=1.).contains(&probability) {
+        Ok(probability)
+    } else {
+        Err(format!("invalid value '{}' for '{}', expected a probability in [0, 1]", value, key))
+    }
+}
+
+// comma separated preset:count[:label][:key=value...] list, e.g. 
+// reptrack:64:villagers:optimistic=false,defector:32
+fn parse_population(value: &str) -> Result<Vec<Group>, String> {
+    value.split(',')
+        .map(|group| {
+            let mut fields = group.split(':'); 
+            let (preset, count) = match (fields.next(), fields.next()) {
+                (Some(preset), Some(count)) => (preset, count), 
+                _ => return Err(format!("invalid population group '{}', expected preset:count[:label]", group)), 
+            }; 
+            let factory = match PRESETS.iter().find(|(name, _)| *name == preset) {
+                Some((_, factory)) => factory, 
+                None => {
+                    let names: Vec<&str> = PRESETS.iter().map(|(name, _)| *name).collect(); 
+                    return Err(format!("unknown preset '{}', available: {}", preset, names.join(", "))); 
+                }
+            }; 
+            let mut result = Group::new(preset, parse("population", count)?); 
+            for field in fields {
+                match field.split_once('=') {
+                    Some(("optimistic", value)) => result.optimistic = Some(parse("optimistic", value)?), 
+                    Some(("threshold", value)) => result.threshold = Some(parse("threshold", value)?), 
+                    Some(("friend-accept", value)) => result.friend_accept = Some(parse("friend-accept", value)?), 
+                    Some(("friend-coop", value)) => result.friend_coop = Some(parse("friend-coop", value)?), 
+                    Some(("drift", value)) => result.drift = Some(parse("drift", value)?), 
+                    Some(("patience", value)) => result.patience = Some(parse("patience", value)?), 
+                    Some((key, _)) => return Err(format!("unknown group parameter '{}' in '{}'", key, group)), 
+                    None if result.label.is_none() && !field.is_empty() => result.label = Some(field.to_string()), 
+                    None => return Err(format!("invalid population group '{}', expected preset:count[:label]", group)), 
+                }
+            }
+            let overridden = result.optimistic.is_some() || result.threshold.is_some(); 
+            if overridden && !factory(&GP, 0).set_disposition(result.optimistic, result.threshold) {
+                return Err(format!("preset '{}' has no optimism or threshold to configure", preset)); 
+            }
+            let friendly = result.friend_accept.is_some() || result.friend_coop.is_some(); 
+            if friendly && !factory(&GP, 0).set_friend_probabilities(result.friend_accept, result.friend_coop) {
+                return Err(format!("preset '{}' has no probabilities to configure", preset)); 
+            }
+            if let Some(step) = result.drift {
+                if !factory(&GP, 0).set_drift(step) {
+                    return Err(format!("preset '{}' doesn't drift", preset)); 
+                }
+            }
+            if let Some(patience) = result.patience {
+                if !factory(&GP, 0).set_patience(patience) {
+                    return Err(format!("preset '{}' has no patience to configure", preset)); 
+                }
+            }
+            Ok(result)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*; 
+
+    // the entries as written to the manifest, parsed back like command line options
+    fn replayed(config: &Config) -> Config {
+        let line: Vec<String> = config.entries().iter().map(|(key, value)| format!("--{} {}", key, value)).collect(); 
+        let line = line.join(" "); 
+        let tokens: Vec<&str> = line.split_whitespace().collect(); 
+        let mut replayed = Config::default(); 
+        for option in tokens.chunks(2) {
+            let key = option[0].strip_prefix("--").unwrap(); 
+            replayed.set(key, option.get(1).copied().unwrap_or_default()).unwrap(); 
+        }
+        replayed
+    }
+
+    #[test]
+    fn entries_round_trip_through_set() {
+        let mut config = Config::default(); 
+        assert_eq!(replayed(&config).entries(), config.entries()); 
+        config.set("matrix-scenarios", "a.txt,scenarios").unwrap(); 
+        config.set("fixation-share", "0.9").unwrap(); 
+        config.set("pair-fraction", "0.5").unwrap(); 
+        assert_eq!(replayed(&config).entries(), config.entries()); 
+    }
+}
@@ -0,0 +1,121 @@
+use std::fs;
+use std::time::SystemTime;
+
+use crate::config::Config;
+
+// options that may change while a run is in progress
+const RELOADABLE: &[&str] = &["report-every", "gossip-rate", "arrivals"]; 
+
+// A file of `--key value` lines (`#` starts a comment), checked at every round 
+// boundary. When it changed, its reloadable options are applied to the running 
+// simulation; everything else is ignored with a warning. 
+pub struct ControlFile {
+    path: String, 
+    modified: Option<SystemTime>, 
+}
+
+impl ControlFile {
+    pub fn new(path: &str) -> ControlFile {
+        ControlFile {
+            path: path.to_string(), 
+            modified: fs::metadata(path).and_then(|meta| meta.modified()).ok(), 
+        }
+    }
+
+    // applies the file to `config` if it changed since the last check; returns 
+    // the (key, value) pairs applied
+    pub fn reload(&mut self, config: &mut Config) -> Vec<(String, String)> {
+        let modified = fs::metadata(&self.path).and_then(|meta| meta.modified()).ok(); 
+        if modified.is_none() || modified == self.modified {
+            return vec![]; 
+        }
+        self.modified = modified; 
+        let content = match fs::read_to_string(&self.path) {
+            Ok(content) => content, 
+            Err(e) => {
+                eprintln!("warning: cannot read control file {}: {}", self.path, e); 
+                return vec![]; 
+            }
+        }; 
+        let mut applied = vec![]; 
+        for line in content.lines().map(|line| line.split('#').next().unwrap().trim()) {
+            if line.is_empty() {
+                continue; 
+            }
+            let (key, value) = match line.split_once(char::is_whitespace) {
+                Some((key, value)) => (key.trim_start_matches("--"), value.trim()), 
+                None => {
+                    eprintln!("warning: control file: expected '--key value', got '{}'", line); 
+                    continue; 
+                }
+            }; 
+            if !RELOADABLE.contains(&key) {
+                eprintln!("warning: control file: '{}' can't be changed during a run, reloadable: {}", key, RELOADABLE.join(", ")); 
+                continue; 
+            }
+            match config.set(key, value) {
+                Ok(()) => applied.push((key.to_string(), value.to_string())), 
+                Err(e) => eprintln!("warning: control file: {}", e), 
+            }
+        }
+        applied
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env; 
+    use std::process; 
+
+    use super::*; 
+
+    // a control file created after the run started, with the given content
+    fn reload(name: &str, content: &str, config: &mut Config) -> (Vec<(String, String)>, ControlFile) {
+        let path = env::temp_dir().join(format!("simulation-control-{}-{}", process::id(), name)); 
+        let path = path.to_string_lossy().into_owned(); 
+        let _ = fs::remove_file(&path); 
+        let mut control = ControlFile::new(&path); 
+        fs::write(&path, content).unwrap(); 
+        let applied = control.reload(config); 
+        (applied, control)
+    }
+
+    #[test]
+    fn applies_reloadable_options_and_skips_comments() {
+        let mut config = Config::default(); 
+        let content = "# tuning\n--gossip-rate 0.25\n\n  report-every 10 # every ten rounds\n"; 
+        let (applied, _) = reload("apply", content, &mut config); 
+        assert_eq!(applied, vec![
+            ("gossip-rate".to_string(), "0.25".to_string()), 
+            ("report-every".to_string(), "10".to_string()), 
+        ]); 
+        assert_eq!(config.gossip_rate, 0.25); 
+    }
+
+    #[test]
+    fn ignores_other_options_invalid_values_and_malformed_lines() {
+        let mut config = Config::default(); 
+        let content = "--rounds 5\n--gossip-rate lots\n--arrivals\n--arrivals 3\n"; 
+        let (applied, _) = reload("ignore", content, &mut config); 
+        assert_eq!(applied, vec![("arrivals".to_string(), "3".to_string())]); 
+        assert_eq!(config.rounds, Config::default().rounds); 
+        assert_eq!(config.gossip_rate, Config::default().gossip_rate); 
+    }
+
+    #[test]
+    fn out_of_range_rates_keep_the_running_value() {
+        let mut config = Config::default(); 
+        config.set("gossip-rate", "0.2").unwrap(); 
+        let (applied, _) = reload("range", "--gossip-rate 15\n", &mut config); 
+        assert!(applied.is_empty()); 
+        assert_eq!(config.gossip_rate, 0.2); 
+    }
+
+    #[test]
+    fn an_unchanged_file_is_not_applied_again() {
+        let mut config = Config::default(); 
+        let (applied, mut control) = reload("unchanged", "--arrivals 3\n", &mut config); 
+        assert_eq!(applied.len(), 1); 
+        assert!(control.reload(&mut config).is_empty()); 
+    }
+}
@@ -0,0 +1,47 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::Agent;
+
+// What changed per strategy type since the previous report: for the delta report, 
+// which shows trends instead of absolute values only. 
+#[derive(Default)]
+pub struct Deltas {
+    // strategy type -> (count, mean energy) at the previous report
+    previous: HashMap<String, (i32, f64)>, 
+    // strategy type -> agents that died since the previous report
+    deaths: BTreeMap<String, u32>, 
+}
+
+impl Deltas {
+    pub fn died(&mut self, agent: &Agent) {
+        *self.deaths.entry(agent.kind()).or_insert(0) += 1; 
+    }
+
+    // (change of count, change of mean energy) since the previous report, None for 
+    // types that weren't present then
+    pub fn change(&self, strategy: &str, count: i32, mean_energy: f64) -> Option<(i32, f64)> {
+        self.previous.get(strategy).map(|(c, e)| (count - c, mean_energy - e))
+    }
+
+    pub fn deaths(&self, strategy: &str) -> u32 {
+        self.deaths.get(strategy).copied().unwrap_or(0)
+    }
+
+    // types present at the previous report that died out since, with their count then
+    pub fn extinct(&self, count: &HashMap<String, i32>) -> Vec<(String, i32)> {
+        let mut extinct: Vec<(String, i32)> = self.previous.iter()
+            .filter(|(strategy, _)| !count.contains_key(*strategy))
+            .map(|(strategy, (c, _))| (strategy.clone(), *c))
+            .collect(); 
+        extinct.sort(); 
+        extinct
+    }
+
+    // starts a new interval from the current state
+    pub fn advance(&mut self, count: &HashMap<String, i32>, sum: &HashMap<String, f64>) {
+        self.previous = count.iter()
+            .map(|(strategy, c)| (strategy.clone(), (*c, sum[strategy] / *c as f64)))
+            .collect(); 
+        self.deaths.clear(); 
+    }
+}
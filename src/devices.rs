@@ -0,0 +1,50 @@
+use crate::config::Config;
+use crate::{Agent, GameParams, Outcome};
+
+// The device an agent lends out. Its quality starts at 1 and drops with every 
+// loan; the use value and theft gain of a loan are proportional to it. 
+#[derive(Debug)]
+pub struct Device {
+    pub quality: f64, 
+    // devices bought to replace worn out or stolen ones
+    pub replacements: u32, 
+}
+
+impl Default for Device {
+    fn default() -> Device {
+        Device { quality: 1., replacements: 0 }
+    }
+}
+
+// payoffs of lending the given device: the borrower's terms scale with its 
+// quality, the lender's are the lending effort and, if stolen, the replacement
+pub fn terms(device: &Device, game: &GameParams) -> GameParams {
+    GameParams {
+        borrower_defect_payout: game.borrower_defect_payout * device.quality, 
+        borrower_coop_payout: game.borrower_coop_payout * device.quality, 
+        ..*game
+    }
+}
+
+// A returned device loses `depreciation` quality, or `careless_factor` times 
+// that if the borrower is careless. A stolen device is replaced right away, the 
+// replacement being what the lender's defect payout already charged; a device 
+// worn below `replacement_quality` is replaced at the same price. 
+pub fn wear(lender: &mut Agent, borrower: &Agent, outcome: Outcome, config: &Config) {
+    let device = &mut lender.device; 
+    match outcome {
+        Outcome::Rejected => return, 
+        Outcome::Defected => {
+            *device = Device { replacements: device.replacements + 1, ..Device::default() }; 
+            return; 
+        }
+        Outcome::Cooperated => {
+            let factor = if borrower.strategy.careless() { config.careless_factor } else { 1. }; 
+            device.quality = (device.quality - config.depreciation * factor).max(0.); 
+        }
+    }
+    if device.quality < config.replacement_quality {
+        *device = Device { replacements: device.replacements + 1, ..Device::default() }; 
+        lender.energy += config.game.lender_defect_payout; 
+    }
+}
@@ -0,0 +1,59 @@
+use std::collections::HashSet;
+use std::io::{self, Write};
+
+use rand::rngs::StdRng;
+use rand::seq::index;
+use rand::SeedableRng;
+
+use crate::config::Config;
+use crate::json::Json;
+use crate::output::OutputFile;
+use crate::{manifest, seed, Agent};
+
+// Periodic dumps of the inspected strategy state of a sample of the initial 
+// agents, one JSON object per agent and dump, for developers of strategies to 
+// check that their bookkeeping evolves as intended. The first line holds the 
+// run manifest. Sampled agents that die drop out of later dumps. 
+pub struct StateDump {
+    out: OutputFile, 
+    every: i32, 
+    ids: HashSet<usize>, 
+}
+
+impl StateDump {
+    pub fn create(path: &str, agents: &[Agent], config: &Config) -> io::Result<StateDump> {
+        let mut out = OutputFile::create(path)?; 
+        let manifest = manifest::lines(config).into_iter().map(Json::from).collect(); 
+        writeln!(out, "{}", Json::object([("manifest", Json::Array(manifest))]))?; 
+        // a stream of its own, so that dumping doesn't change the run
+        let mut rng = StdRng::seed_from_u64(seed::derive(config.seed, seed::DUMP)); 
+        let ids = match config.dump_agents {
+            0 => agents.iter().map(|agent| agent.id).collect(), 
+            n => index::sample(&mut rng, agents.len(), n.min(agents.len()))
+                .into_iter()
+                .map(|i| agents[i].id)
+                .collect(), 
+        }; 
+        Ok(StateDump { out, every: config.dump_every.max(1), ids })
+    }
+
+    pub fn due(&self, round: i32) -> bool {
+        (round + 1) % self.every == 0
+    }
+
+    // `round` is the number of completed rounds
+    pub fn write(&mut self, round: i32, agents: &[Agent]) -> io::Result<()> {
+        let StateDump { out, ids, .. } = self; 
+        for agent in agents.iter().filter(|agent| ids.contains(&agent.id)) {
+            let line = Json::object([
+                ("round", Json::Number(round as f64)), 
+                ("id", agent.id.into()), 
+                ("kind", agent.kind().into()), 
+                ("energy", agent.energy.into()), 
+                ("state", agent.strategy.inspect()), 
+            ]); 
+            writeln!(out, "{}", line)?; 
+        }
+        Ok(())
+    }
+}
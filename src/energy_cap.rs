@@ -0,0 +1,48 @@
+use crate::config::CapMode;
+use crate::Agent;
+
+// share of the energy above a soft cap an agent keeps per round
+const SOFT_CAP_RETENTION: f64 = 0.5; 
+
+// How often the energy cap bound: agent rounds that ended above the cap and the 
+// energy taken off. 
+#[derive(Default)]
+pub struct CapStats {
+    pub binds: u64, 
+    pub agent_rounds: u64, 
+    pub clipped: f64, 
+}
+
+impl CapStats {
+    // A hard cap cuts the energy of every agent down to the cap at the end of the 
+    // round, a soft cap takes half of the excess, so that early winners keep 
+    // growing, but ever more slowly. 
+    pub fn apply(&mut self, agents: &mut [Agent], cap: f64, mode: CapMode) {
+        for agent in agents.iter_mut() {
+            self.agent_rounds += 1; 
+            if agent.energy <= cap {
+                continue; 
+            }
+            let excess = agent.energy - cap; 
+            let kept = match mode {
+                CapMode::Hard => 0., 
+                CapMode::Soft => excess * SOFT_CAP_RETENTION, 
+            }; 
+            agent.energy = cap + kept; 
+            self.binds += 1; 
+            self.clipped += excess - kept; 
+        }
+    }
+
+    pub fn report(&self, cap: f64, mode: CapMode) {
+        let share = if self.agent_rounds == 0 { 0. } else { self.binds as f64 / self.agent_rounds as f64 }; 
+        println!(
+            "energy cap ({} {}): bound {} times ({:.3} of agent rounds), {:.1} energy clipped", 
+            mode, 
+            cap, 
+            self.binds, 
+            share, 
+            self.clipped
+        ); 
+    }
+}
@@ -0,0 +1,63 @@
+use crate::{lend, pair_mut, Agent, GameParams, Outcome};
+
+// Escrow as a second chance for a rejected borrower: the lender may offer the loan 
+// under escrow and the borrower may agree to it. The borrower then deposits 
+// collateral with the engine, covering the lender's loss compared to a returned 
+// device, and pays the escrow fee. The collateral goes to the lender if the device 
+// isn't returned and back to the borrower otherwise. 
+pub fn attempt(agents: &mut [Agent], lender: usize, borrower: usize, fee: f64, game: &GameParams) -> Outcome {
+    let collateral = game.lender_coop_payout - game.lender_defect_payout; 
+    let (l, b) = pair_mut(agents, lender, borrower); 
+    if b.energy <= collateral + fee 
+        || !l.strategy.accept_with_escrow(b.id) 
+        || !b.strategy.agree_to_escrow(l.id, collateral) {
+        return Outcome::Rejected; 
+    }
+    b.energy -= collateral + fee; 
+    let outcome = lend(l, b, game); 
+    if outcome == Outcome::Cooperated {
+        b.energy += collateral; 
+    } else {
+        l.energy += collateral; 
+        l.lost_to_defections -= collateral; 
+    }
+    outcome
+}
+
+// loans and returned devices with and without escrow, for comparing cooperation
+#[derive(Default)]
+pub struct EscrowStats {
+    direct: (u64, u64), 
+    escrowed: (u64, u64), 
+    fees: f64, 
+}
+
+impl EscrowStats {
+    pub fn record(&mut self, outcome: Outcome, escrowed: bool, fee: f64) {
+        if outcome == Outcome::Rejected {
+            return; 
+        }
+        let entry = if escrowed {
+            self.fees += fee; 
+            &mut self.escrowed
+        } else {
+            &mut self.direct
+        };
+        entry.0 += 1; 
+        if outcome == Outcome::Cooperated {
+            entry.1 += 1; 
+        }
+    }
+
+    pub fn report(&self) {
+        let rate = |(loans, returned): (u64, u64)| if loans == 0 { 0. } else { returned as f64 / loans as f64 }; 
+        println!(
+            "escrow: {} direct loans ({:.3} returned), {} escrowed loans ({:.3} returned), {:.1} fees paid", 
+            self.direct.0, 
+            rate(self.direct), 
+            self.escrowed.0, 
+            rate(self.escrowed), 
+            self.fees
+        ); 
+    }
+}
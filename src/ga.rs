@@ -0,0 +1,245 @@
+use core::fmt;
+use std::io::Write;
+use std::process;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::config::{Command, Config};
+use crate::output::{exit_on_error, OutputFile};
+use crate::{manifest, seed, simulate, Agent, GameParams, RandomStrategy, ReputationTracker, Strategy};
+
+// the evolvable parameters of one agent
+#[derive(Clone)]
+pub enum Genome {
+    Tracker { optimistic: bool, threshold: f64 }, 
+    Random { accept_prob: f32, coop_prob: f32 }, 
+}
+
+const THRESHOLD_RANGE: f64 = 10.; 
+
+impl Genome {
+    fn random<R: Rng>(tracker: bool, rng: &mut R) -> Genome {
+        if tracker {
+            Genome::Tracker {
+                optimistic: rng.gen(), 
+                threshold: rng.gen_range(-THRESHOLD_RANGE..THRESHOLD_RANGE), 
+            }
+        } else {
+            Genome::Random {
+                accept_prob: rng.gen(), 
+                coop_prob: rng.gen(), 
+            }
+        }
+    }
+
+    pub fn build(&self, game: &GameParams, seed: u64) -> Box<dyn Strategy> {
+        match self {
+            Genome::Tracker { optimistic, threshold } => 
+                Box::new(ReputationTracker::new(*optimistic, *threshold, *game)), 
+            Genome::Random { accept_prob, coop_prob } => 
+                Box::new(RandomStrategy::new(*accept_prob, *coop_prob, "random".into(), seed)), 
+        }
+    }
+
+    // uniform crossover; genomes of different kinds can't be mixed, so the child 
+    // is then a copy of self
+    fn crossover<R: Rng>(&self, other: &Genome, rng: &mut R) -> Genome {
+        match (self, other) {
+            (
+                Genome::Tracker { optimistic: o1, threshold: t1 }, 
+                Genome::Tracker { optimistic: o2, threshold: t2 }
+            ) => Genome::Tracker {
+                optimistic: if rng.gen() { *o1 } else { *o2 }, 
+                threshold: if rng.gen() { *t1 } else { *t2 }, 
+            }, 
+            (
+                Genome::Random { accept_prob: a1, coop_prob: c1 }, 
+                Genome::Random { accept_prob: a2, coop_prob: c2 }
+            ) => Genome::Random {
+                accept_prob: if rng.gen() { *a1 } else { *a2 }, 
+                coop_prob: if rng.gen() { *c1 } else { *c2 }, 
+            }, 
+            _ => self.clone()
+        }
+    }
+
+    // every gene is perturbed with probability `rate`
+    fn mutate<R: Rng>(&mut self, rate: f64, rng: &mut R) {
+        match self {
+            Genome::Tracker { optimistic, threshold } => {
+                if rng.gen_bool(rate) {
+                    *optimistic = !*optimistic; 
+                }
+                if rng.gen_bool(rate) {
+                    *threshold = (*threshold + rng.gen_range(-1.0..1.0))
+                        .clamp(-THRESHOLD_RANGE, THRESHOLD_RANGE); 
+                }
+            }, 
+            Genome::Random { accept_prob, coop_prob } => {
+                if rng.gen_bool(rate) {
+                    *accept_prob = (*accept_prob + rng.gen_range(-0.1..0.1)).clamp(0., 1.); 
+                }
+                if rng.gen_bool(rate) {
+                    *coop_prob = (*coop_prob + rng.gen_range(-0.1..0.1)).clamp(0., 1.); 
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for Genome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Genome::Tracker { optimistic, threshold } => 
+                write!(f, "tracker optimistic={} threshold={:.3}", optimistic, threshold), 
+            Genome::Random { accept_prob, coop_prob } => 
+                write!(f, "random accept_prob={:.3} coop_prob={:.3}", accept_prob, coop_prob), 
+        }
+    }
+}
+
+// index of the fittest of three randomly drawn genomes
+fn tournament<R: Rng>(fitness: &[f64], rng: &mut R) -> usize {
+    (0..3)
+        .map(|_| rng.gen_range(0..fitness.len()))
+        .max_by(|a, b| fitness[*a].partial_cmp(&fitness[*b]).unwrap())
+        .unwrap()
+}
+
+// Fitness per genome, indexed like the population. Agents that entered during the 
+// run (arrivals, whitewashed identities, offspring) carry no genome and are skipped. 
+fn fitness(agents: &[Agent], size: usize, config: &Config) -> Vec<f64> {
+    let mut fitness = vec![0.; size]; 
+    for agent in agents.iter().filter(|agent| agent.id < size) {
+        fitness[agent.id] = agent.fitness(config); 
+    }
+    fitness
+}
+
+const DEVICE_VALUE_RANGE: (f64, f64) = (0.1, 10.); 
+
+// Evolves a population of genomes, half trackers and half random strategies 
+// initially. Every generation all genomes play one simulation against each other; 
+// an agent's fitness is its final energy, or 0 if it died. 
+// In coevolution mode the device value (relative to the configured payoffs) 
+// additionally adapts to the lending rate of the previous generation. 
+pub fn run(config: &Config) {
+    for line in manifest::lines(config) {
+        println!("# {}", line); 
+    }
+    // stream 0 drives the algorithm itself, generation g runs with stream g + 1
+    let mut rng = StdRng::seed_from_u64(seed::derive(config.seed, 0)); 
+    let size = config.ga_population.max(2); 
+    let elite = config.elite.min(size); 
+    let mut run_config = Config {
+        quiet: true, 
+        regret_out: None, 
+        dump_out: None, 
+        snapshots: 0, 
+        axelrod_summary: None, 
+        axelrod_matrix: None, 
+        ..config.clone()
+    };
+    let mut device_value = 1.; 
+
+    let mut out = config.ga_out.as_ref().map(|path| {
+        let mut file = OutputFile::create(path).unwrap_or_else(|e| {
+            eprintln!("error: cannot write {}: {}", path, e); 
+            process::exit(1); 
+        });
+        let header = manifest::write(&mut file, config)
+            .and_then(|_| writeln!(file, "generation,rank,fitness,lending_rate,device_value,genome")); 
+        exit_on_error(header, path); 
+        (path, file)
+    });
+
+    let mut population: Vec<Genome> = (0..size)
+        .map(|i| Genome::random(i % 2 == 0, &mut rng))
+        .collect(); 
+
+    for generation in 0..config.generations {
+        run_config.game = config.game.scale_device_value(device_value); 
+        run_config.seed = seed::derive(config.seed, generation as u64 + 1); 
+        let mut agents: Vec<Agent> = population.iter()
+            .enumerate()
+            .map(|(id, genome)| {
+                Agent::new(id, genome.build(&run_config.game, seed::derive(run_config.seed, id as u64)))
+            })
+            .collect(); 
+        let summary = simulate(&mut agents, &run_config); 
+        let lending_rate = if summary.requests == 0 {
+            0.
+        } else {
+            summary.loans as f64 / summary.requests as f64
+        };
+
+        let fitness = fitness(&agents, size, config); 
+        let mut ranked: Vec<usize> = (0..size).collect(); 
+        ranked.sort_by(|a, b| fitness[*b].partial_cmp(&fitness[*a]).unwrap()); 
+
+        println!(
+            "Generation {}: device value {:.3}, lending rate {:.3}, {} survivors, mean fitness {:.2}, best {:.2} ({})", 
+            generation, 
+            device_value, 
+            lending_rate, 
+            agents.len(), 
+            fitness.iter().sum::<f64>() / size as f64, 
+            fitness[ranked[0]], 
+            population[ranked[0]]
+        ); 
+        if let Some((path, file)) = out.as_mut() {
+            for (rank, i) in ranked.iter().take(elite.max(1)).enumerate() {
+                let row = writeln!(
+                    file, 
+                    "{},{},{:.4},{:.4},{:.4},{}", 
+                    generation, 
+                    rank, 
+                    fitness[*i], 
+                    lending_rate, 
+                    device_value, 
+                    population[*i]
+                ); 
+                exit_on_error(row, path); 
+            }
+        }
+
+        if let Command::Coevolve = config.command {
+            device_value = (device_value * (1. + config.drift_rate * (config.target_lending - lending_rate)))
+                .clamp(DEVICE_VALUE_RANGE.0, DEVICE_VALUE_RANGE.1); 
+        }
+
+        let mut next: Vec<Genome> = ranked.iter()
+            .take(elite)
+            .map(|i| population[*i].clone())
+            .collect(); 
+        while next.len() < size {
+            let a = tournament(&fitness, &mut rng); 
+            let b = tournament(&fitness, &mut rng); 
+            let mut child = population[a].crossover(&population[b], &mut rng); 
+            child.mutate(config.mutation_rate, &mut rng); 
+            next.push(child); 
+        }
+        population = next; 
+    }
+    if let Some((path, file)) = out.as_mut() {
+        exit_on_error(file.flush(), path); 
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*; 
+
+    fn agent(id: usize, energy: f64) -> Agent {
+        let mut agent = Agent::new(id, Box::new(RandomStrategy::new(0.5, 0.5, "random".to_string(), 0))); 
+        agent.energy = energy; 
+        agent
+    }
+
+    #[test]
+    fn fitness_skips_agents_without_a_genome() {
+        let agents = vec![agent(0, 3.), agent(2, 5.), agent(8, 7.)]; 
+        assert_eq!(fitness(&agents, 3, &Config::default()), vec![3., 0., 5.]); 
+    }
+}
@@ -0,0 +1,106 @@
+use std::fs; 
+use std::io::Write; 
+use std::path::Path; 
+use std::process; 
+
+use rand::rngs::StdRng; 
+use rand::seq::SliceRandom; 
+use rand::{Rng, SeedableRng}; 
+
+use crate::config::{Config, Range, TopologyKind}; 
+use crate::output::OutputFile; 
+use crate::{seed, GameParams, PRESETS}; 
+
+// payoff matrices drawn before giving up on the configured ranges
+const MAX_PAYOFF_DRAWS: usize = 1000; 
+
+fn sample(range: &Range, rng: &mut StdRng) -> f64 {
+    if range.min == range.max {
+        range.min
+    } else {
+        rng.gen_range(range.min..=range.max)
+    }
+}
+
+// A payoff matrix from the meta-tournament ranges that keeps the dilemma: stealing 
+// tempts the borrower more than returning, a returned loan creates value and a 
+// stolen one destroys it. 
+fn payoffs(config: &Config, rng: &mut StdRng) -> Option<GameParams> {
+    (0..MAX_PAYOFF_DRAWS)
+        .map(|_| GameParams {
+            borrower_defect_payout: sample(&config.borrower_defect_range, rng), 
+            borrower_coop_payout: sample(&config.borrower_coop_range, rng), 
+            lender_defect_payout: sample(&config.lender_defect_range, rng), 
+            lender_coop_payout: sample(&config.lender_coop_range, rng), 
+        })
+        .find(|game| {
+            let cooperation = game.borrower_coop_payout + game.lender_coop_payout; 
+            game.borrower_defect_payout > game.borrower_coop_payout
+                && cooperation > 0.
+                && game.borrower_defect_payout + game.lender_defect_payout < cooperation
+        })
+}
+
+// the options of one scenario, as (key, value) pairs
+fn scenario(config: &Config, rng: &mut StdRng) -> Vec<(&'static str, String)> {
+    let presets = rng.gen_range(2..=4); 
+    let population: Vec<String> = PRESETS.choose_multiple(rng, presets)
+        .map(|(name, _)| format!("{}:{}", name, rng.gen_range(8..=64)))
+        .collect(); 
+    let game = payoffs(config, rng).unwrap_or_else(|| {
+        eprintln!("error: the payoff ranges allow no matrix where stealing tempts and lending creates value"); 
+        process::exit(2); 
+    }); 
+    let topology = *[TopologyKind::Complete, TopologyKind::Random, TopologyKind::ScaleFree].choose(rng).unwrap(); 
+    let mut options = vec![
+        ("seed", rng.gen::<u64>().to_string()), 
+        ("population", population.join(",")), 
+        ("borrower-defect-payout", format!("{:.2}", game.borrower_defect_payout)), 
+        ("borrower-coop-payout", format!("{:.2}", game.borrower_coop_payout)), 
+        ("lender-defect-payout", format!("{:.2}", game.lender_defect_payout)), 
+        ("lender-coop-payout", format!("{:.2}", game.lender_coop_payout)), 
+        ("topology", topology.to_string()), 
+    ]; 
+    if !matches!(topology, TopologyKind::Complete) {
+        options.push(("degree", rng.gen_range(2..=12).to_string())); 
+    }
+    options
+}
+
+// Scenario generator: writes `scenarios` files of randomized options (population 
+// mix of 2 to 4 presets with 8 to 64 agents each, payoff matrix, topology and 
+// seed) to the scenario directory, to be run with --scenario. Every scenario is 
+// validated by applying it to a configuration before it is written; the same 
+// seed generates the same corpus. 
+pub fn run(config: &Config) {
+    let dir = Path::new(&config.scenario_dir); 
+    if let Err(e) = fs::create_dir_all(dir) {
+        eprintln!("error: cannot create {}: {}", dir.display(), e); 
+        process::exit(1); 
+    }
+    for i in 0..config.scenarios {
+        let mut rng = StdRng::seed_from_u64(seed::derive(config.seed, i as u64)); 
+        let options = scenario(config, &mut rng); 
+        let mut check = Config::default(); 
+        for (key, value) in options.iter() {
+            if let Err(e) = check.set(key, value) {
+                eprintln!("error: generated an invalid scenario: {}", e); 
+                process::exit(1); 
+            }
+        }
+        let path = dir.join(format!("scenario-{:03}.txt", i)); 
+        let written = OutputFile::create(&path.to_string_lossy()).and_then(|mut file| {
+            writeln!(file, "# scenario {} generated from seed {}", i, config.seed)?; 
+            for (key, value) in options.iter() {
+                writeln!(file, "--{} {}", key, value)?; 
+            }
+            Ok(())
+        }); 
+        if let Err(e) = written {
+            eprintln!("error: cannot write {}: {}", path.display(), e); 
+            process::exit(1); 
+        }
+        let line: Vec<String> = options.iter().map(|(key, value)| format!("--{} {}", key, value)).collect(); 
+        println!("{}: {}", path.display(), line.join(" ")); 
+    }
+}
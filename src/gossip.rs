@@ -0,0 +1,43 @@
+use std::collections::HashMap;
+
+use rand::rngs::StdRng;
+use rand::Rng;
+
+use crate::collusion::InteractionLog;
+use crate::Agent;
+
+// Every agent, with probability `rate`, passes its current gossip on to one 
+// randomly chosen other agent. False accusations are counted on their subject. 
+pub fn exchange(agents: &mut [Agent], rate: f64, log: &mut InteractionLog, rng: &mut StdRng) {
+    if agents.len() < 2 {
+        return; 
+    }
+    let index: HashMap<usize, usize> = agents.iter()
+        .enumerate()
+        .map(|(i, agent)| (agent.id, i))
+        .collect(); 
+    for speaker in 0..agents.len() {
+        if !rng.gen_bool(rate) {
+            continue; 
+        }
+        let mut listener = rng.gen_range(0..agents.len() - 1); 
+        if listener >= speaker {
+            listener += 1; 
+        }
+        let source = agents[speaker].id; 
+        let reports = agents[speaker].strategy.gossip(); 
+        for (subject, rating) in reports {
+            if subject != agents[listener].id {
+                agents[listener].strategy.hear_gossip(source, subject, rating); 
+                if rating > 0. {
+                    log.record_praise(source, subject); 
+                }
+                if let Some(s) = index.get(&subject) {
+                    if agents[*s].is_slandered_by(rating) {
+                        agents[*s].false_negative_reports += 1; 
+                    }
+                }
+            }
+        }
+    }
+}
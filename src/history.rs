@@ -0,0 +1,71 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::autopsy::Partners;
+use crate::memory;
+use crate::Outcome;
+
+// what happened when one agent asked another for a device
+#[derive(Clone, Copy, Default)]
+pub struct PairRecord {
+    pub cooperations: u32, 
+    pub defections: u32, 
+    pub rejections: u32, 
+}
+
+// The canonical per-pair interaction history kept by the engine: every request, 
+// keyed by (lender, borrower). Both directions of a pair are separate entries. 
+#[derive(Default)]
+pub struct PairHistory {
+    records: HashMap<(usize, usize), PairRecord>, 
+}
+
+impl PairHistory {
+    pub fn record(&mut self, lender: usize, borrower: usize, outcome: Outcome) {
+        let record = self.records.entry((lender, borrower)).or_default(); 
+        match outcome {
+            Outcome::Cooperated => record.cooperations += 1, 
+            Outcome::Defected => record.defections += 1, 
+            Outcome::Rejected => record.rejections += 1, 
+        }
+    }
+
+    // whether the two agents ever met, in either role
+    pub fn met(&self, a: usize, b: usize) -> bool {
+        self.records.contains_key(&(a, b)) || self.records.contains_key(&(b, a))
+    }
+
+    // approximate heap memory in bytes
+    pub fn memory(&self) -> usize {
+        memory::map_bytes::<(usize, usize), PairRecord>(self.records.len())
+    }
+}
+
+// Read-only handle on the engine's history, shared by all strategies that ask for it. 
+#[derive(Clone)]
+pub struct HistoryView(Rc<RefCell<PairHistory>>);
+
+impl HistoryView {
+    pub fn new(history: &Rc<RefCell<PairHistory>>) -> HistoryView {
+        HistoryView(Rc::clone(history))
+    }
+
+    // requests `borrower` made to `lender`, all zero if there were none
+    pub fn get(&self, lender: usize, borrower: usize) -> PairRecord {
+        self.0.borrow().records.get(&(lender, borrower)).copied().unwrap_or_default()
+    }
+
+    // partner -> (requests the partner made to `id`, requests `id` made to the partner)
+    pub fn partners(&self, id: usize) -> Partners {
+        let mut partners = Partners::new(); 
+        for ((lender, borrower), record) in self.0.borrow().records.iter() {
+            if *lender == id {
+                partners.entry(*borrower).or_default().0 = *record; 
+            } else if *borrower == id {
+                partners.entry(*lender).or_default().1 = *record; 
+            }
+        }
+        partners
+    }
+}
@@ -0,0 +1,52 @@
+use std::collections::HashMap;
+
+use crate::history::PairHistory;
+use crate::Agent;
+
+// What the engine makes public about an agent to imitators: its current energy 
+// (the payoffs it realized, costs included) and how it behaved, as the engine 
+// observed it - the share of requests it accepted as a lender and of devices it 
+// returned as a borrower, None before its first request in that role. 
+pub struct Performance {
+    pub id: usize, 
+    pub energy: f64, 
+    pub accept_rate: Option<f64>, 
+    pub coop_rate: Option<f64>, 
+}
+
+impl Performance {
+    fn of(agent: &Agent) -> Performance {
+        Performance {
+            id: agent.id, 
+            energy: agent.energy, 
+            accept_rate: agent.acceptance_rate(), 
+            coop_rate: agent.cooperation_rate(), 
+        }
+    }
+}
+
+// Tells every agent that asks for it the performance of the partners it names. 
+// Only living partners it actually met (in either role) are disclosed, so 
+// strategies can't learn about the rest of the population this way. 
+pub fn inform(agents: &mut [Agent], index: &HashMap<usize, usize>, history: &PairHistory) {
+    let requests: Vec<(usize, usize, Vec<usize>)> = agents.iter_mut()
+        .enumerate()
+        .filter_map(|(i, agent)| {
+            let candidates = agent.strategy.imitation_candidates(); 
+            (!candidates.is_empty()).then_some((i, agent.id, candidates))
+        })
+        .collect(); 
+    let observations: Vec<(usize, Vec<Performance>)> = requests.into_iter()
+        .map(|(i, id, candidates)| {
+            let performance = candidates.into_iter()
+                .filter(|partner| history.met(id, *partner))
+                .filter_map(|partner| index.get(&partner))
+                .map(|j| Performance::of(&agents[*j]))
+                .collect(); 
+            (i, performance)
+        })
+        .collect(); 
+    for (i, performance) in observations {
+        agents[i].strategy.observe_performance(&performance); 
+    }
+}
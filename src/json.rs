@@ -0,0 +1,146 @@
+use core::fmt;
+use std::collections::HashMap;
+
+// A JSON value, written compactly by Display. Used for strategy introspection 
+// (Strategy::inspect) and everything that shows it, without a serialization 
+// framework. Non-finite numbers are written as null. 
+pub enum Json {
+    Null, 
+    Bool(bool), 
+    Number(f64), 
+    String(String), 
+    Array(Vec<Json>), 
+    Object(Vec<(String, Json)>), 
+}
+
+impl Json {
+    pub fn object<'a>(fields: impl IntoIterator<Item = (&'a str, Json)>) -> Json {
+        Json::Object(fields.into_iter().map(|(key, value)| (key.to_string(), value)).collect())
+    }
+
+    // partner -> score tables, keyed by partner id in ascending order
+    pub fn scores(scores: &HashMap<usize, f64>) -> Json {
+        let mut entries: Vec<(&usize, &f64)> = scores.iter().collect(); 
+        entries.sort_unstable_by_key(|(id, _)| **id); 
+        Json::Object(entries.into_iter().map(|(id, score)| (id.to_string(), Json::Number(*score))).collect())
+    }
+
+    // this object with the field set to `value`, replacing an existing one
+    pub fn with(self, key: &str, value: Json) -> Json {
+        match self {
+            Json::Object(mut fields) => {
+                match fields.iter_mut().find(|(k, _)| k == key) {
+                    Some(field) => field.1 = value, 
+                    None => fields.push((key.to_string(), value)), 
+                }
+                Json::Object(fields)
+            }, 
+            other => other, 
+        }
+    }
+
+    // this object without the field
+    pub fn without(self, key: &str) -> Json {
+        match self {
+            Json::Object(fields) => Json::Object(fields.into_iter().filter(|(k, _)| k != key).collect()), 
+            other => other, 
+        }
+    }
+}
+
+impl From<bool> for Json {
+    fn from(value: bool) -> Json {
+        Json::Bool(value)
+    }
+}
+
+impl From<f64> for Json {
+    fn from(value: f64) -> Json {
+        Json::Number(value)
+    }
+}
+
+impl From<f32> for Json {
+    fn from(value: f32) -> Json {
+        Json::Number(value as f64)
+    }
+}
+
+impl From<u32> for Json {
+    fn from(value: u32) -> Json {
+        Json::Number(value as f64)
+    }
+}
+
+impl From<usize> for Json {
+    fn from(value: usize) -> Json {
+        Json::Number(value as f64)
+    }
+}
+
+impl From<String> for Json {
+    fn from(value: String) -> Json {
+        Json::String(value)
+    }
+}
+
+impl From<&str> for Json {
+    fn from(value: &str) -> Json {
+        Json::String(value.to_string())
+    }
+}
+
+impl<T: Into<Json>> From<Option<T>> for Json {
+    fn from(value: Option<T>) -> Json {
+        value.map_or(Json::Null, Into::into)
+    }
+}
+
+fn write_string(f: &mut fmt::Formatter, s: &str) -> fmt::Result {
+    write!(f, "\"")?; 
+    for c in s.chars() {
+        match c {
+            '"' => write!(f, "\\\"")?, 
+            '\\' => write!(f, "\\\\")?, 
+            '\n' => write!(f, "\\n")?, 
+            '\r' => write!(f, "\\r")?, 
+            '\t' => write!(f, "\\t")?, 
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?, 
+            c => write!(f, "{}", c)?, 
+        }
+    }
+    write!(f, "\"")
+}
+
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Json::Null => write!(f, "null"), 
+            Json::Bool(value) => write!(f, "{}", value), 
+            Json::Number(value) if value.is_finite() => write!(f, "{}", value), 
+            Json::Number(_) => write!(f, "null"), 
+            Json::String(value) => write_string(f, value), 
+            Json::Array(values) => {
+                write!(f, "[")?; 
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?; 
+                    }
+                    write!(f, "{}", value)?; 
+                }
+                write!(f, "]")
+            }, 
+            Json::Object(fields) => {
+                write!(f, "{{")?; 
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?; 
+                    }
+                    write_string(f, key)?; 
+                    write!(f, ":{}", value)?; 
+                }
+                write!(f, "}}")
+            }, 
+        }
+    }
+}
@@ -0,0 +1,26 @@
+use crate::{pair_mut, Agent, Outcome};
+
+// Experimental karma tokens: every returned device mints a token for the borrower. 
+// Before asking a lender, the borrower may offer some of its tokens as a signal of 
+// trustworthiness; if the lender accepts the signal, the tokens are transferred to 
+// it and can be spent again in its own encounters as a borrower. 
+pub fn signal(agents: &mut [Agent], l: usize, b: usize) {
+    let (lender, borrower) = pair_mut(agents, l, b); 
+    if borrower.karma == 0 {
+        return; 
+    }
+    let tokens = borrower.strategy.offer_karma(lender.id, borrower.karma).min(borrower.karma); 
+    if tokens == 0 || !lender.strategy.accept_karma(borrower.id, tokens) {
+        return; 
+    }
+    borrower.karma -= tokens; 
+    borrower.karma_spent += tokens; 
+    lender.karma += tokens; 
+}
+
+pub fn mint(borrower: &mut Agent, outcome: Outcome) {
+    if outcome == Outcome::Cooperated {
+        borrower.karma += 1; 
+        borrower.karma_minted += 1; 
+    }
+}
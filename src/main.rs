@@ -1,8 +1,91 @@
-use rand::rngs::ThreadRng;
-use rand::Rng; 
+use rand::rngs::StdRng;
+use rand::distributions::uniform::SampleUniform;
+use rand::{Rng, SeedableRng}; 
 use core::fmt;
-use std::collections::HashMap;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::io::{self, Write};
+use std::ops::{Add, Neg};
+use std::process;
+use std::rc::Rc;
 
+mod arrivals;
+mod autopsy;
+mod axelrod;
+mod budget;
+mod check;
+mod collusion;
+mod config;
+mod control;
+mod deltas;
+mod devices;
+mod dump;
+mod energy_cap;
+mod escrow;
+mod ga;
+mod generate;
+mod gossip;
+mod history;
+mod imitation;
+mod json;
+mod karma;
+mod manifest;
+mod matchmaking;
+mod memory;
+mod meta;
+mod metrics;
+mod network;
+mod output;
+mod postmortem;
+mod profile;
+mod referral;
+mod regret;
+mod reporter;
+mod reproduction;
+mod scoring;
+mod seed;
+mod seed_matrix;
+mod seed_search;
+mod signaling;
+mod signals;
+mod self_report;
+mod stopping;
+mod sweep;
+mod terminal;
+mod topology;
+mod trust;
+mod vouching;
+mod whitewash;
+mod witness;
+
+use autopsy::{Graveyard, Trajectory};
+use axelrod::CrossTable;
+use budget::EncounterBudget;
+use collusion::InteractionLog;
+use config::{Command, Config, Group, TopologyKind};
+use control::ControlFile;
+use deltas::Deltas;
+use devices::Device;
+use dump::StateDump;
+use energy_cap::CapStats;
+use escrow::EscrowStats;
+use history::{HistoryView, PairHistory};
+use imitation::Performance;
+use json::Json;
+use memory::MemoryMonitor;
+use postmortem::PostMortem;
+use profile::Profile;
+use regret::RegretTracker;
+use reporter::Reporter;
+use reproduction::Births;
+use stopping::{StopDetector, StopReason};
+use terminal::Progress;
+use topology::Topology;
+use trust::TrustGraph;
+use vouching::Sponsorships;
+use whitewash::Whitewashing;
+
+#[derive(Clone, Copy)]
 struct GameParams {
     borrower_defect_payout: f64, 
     borrower_coop_payout: f64, 
@@ -10,6 +93,20 @@ struct GameParams {
     lender_coop_payout: f64
 }
 
+impl GameParams {
+    // payoffs with the device dependent terms (use value, theft gain and loss) 
+    // multiplied by `factor`; the lending effort is not affected
+    fn scale_device_value(&self, factor: f64) -> GameParams {
+        GameParams {
+            borrower_defect_payout: self.borrower_defect_payout * factor, 
+            borrower_coop_payout: self.borrower_coop_payout * factor, 
+            lender_defect_payout: self.lender_defect_payout * factor, 
+            lender_coop_payout: self.lender_coop_payout, 
+        }
+    }
+}
+
+// default payoffs, can be overridden from the command line
 const GP: GameParams = GameParams {
     borrower_defect_payout: 6., // steals the device
     borrower_coop_payout: 3., // uses the device
@@ -31,19 +128,234 @@ trait Strategy : ToString {
     fn coop_or_defect(&mut self, lender: usize) -> LenderAction; 
     fn notify_coop_or_defect(&mut self, borrower: usize, coop: BorrowerAction); 
     fn get_type(&self) -> String;
+    // internal partner scores, for strategies that keep them; used to evaluate 
+    // how well the scores predict actual behavior
+    fn reputations(&self) -> Option<&HashMap<usize, f64>> {
+        None
+    }
+    // the strategy's internal state (parameters, learned values, reputation 
+    // table) for reports and dumps; strategies with state of their own add it
+    fn inspect(&self) -> Json {
+        Json::object([
+            ("type", self.get_type().into()), 
+            ("reputations", self.reputations().map_or(Json::Null, Json::scores)), 
+        ])
+    }
+    // whether the strategy learns from its history; regret is tracked for these
+    fn is_adaptive(&self) -> bool {
+        false
+    }
+    // reports (subject, rating) this agent passes on when it gossips; positive 
+    // ratings recommend the subject, negative ones warn about it
+    fn gossip(&mut self) -> Vec<(usize, f64)> {
+        vec![]
+    }
+    fn hear_gossip(&mut self, _source: usize, _subject: usize, _rating: f64) {
+    }
+    // whether to query witnesses about a borrower before deciding on its request; 
+    // their answers are delivered through hear_gossip
+    fn wants_witnesses(&mut self, _borrower: usize) -> bool {
+        false
+    }
+    // this agent's rating of a subject when asked as a witness, if it has one
+    fn asked_about(&self, _subject: usize) -> Option<f64> {
+        None
+    }
+    // whether the engine should tell this agent its transitive trust in a borrower 
+    // before it decides on the request
+    fn wants_transitive_trust(&mut self, _borrower: usize) -> bool {
+        false
+    }
+    // strongest trust path of bounded length from this agent to the borrower, see 
+    // TrustGraph::transitive_trust
+    fn hear_transitive_trust(&mut self, _borrower: usize, _trust: Option<f64>) {
+    }
+    // called once at spawn time with the ids of all agents of the same population 
+    // group, including this one
+    fn meet_accomplices(&mut self, _accomplices: &[usize]) {
+    }
+    // loans this agent claims to have made to the given borrowers although they 
+    // never happened; recorded if the borrower confirms them
+    fn self_reported_encounters(&mut self) -> Vec<usize> {
+        vec![]
+    }
+    fn confirm_self_report(&mut self, _lender: usize) -> bool {
+        false
+    }
+    // whether to stake energy on a newcomer, see vouching::Sponsorships
+    fn sponsor_newcomer(&mut self, _newcomer: usize) -> bool {
+        false
+    }
+    // announces that an agent vouched for a newcomer
+    fn hear_vouch(&mut self, _voucher: usize, _newcomer: usize) {
+    }
+    // karma: how many of its tokens to offer a lender as a signal of trustworthiness
+    fn offer_karma(&mut self, _lender: usize, _balance: u32) -> u32 {
+        0
+    }
+    // whether to take the offered tokens, and be swayed by them
+    fn accept_karma(&mut self, _borrower: usize, _tokens: u32) -> bool {
+        false
+    }
+    // called at the end of every round with the discount factor for past experience
+    fn discount_history(&mut self, _factor: f64) {
+    }
+    // called at the end of every round with the share of bad experience to forgive
+    fn forgive(&mut self, _share: f64) {
+    }
+    // called at the end of every round, for behavior that changes over time
+    fn end_round(&mut self) {
+    }
+    // called when the warm-up ends, for counters the strategy keeps for the report
+    fn reset_statistics(&mut self) {
+    }
+    // whether to leave and re-enter as a newcomer, given the rounds since the 
+    // current identity was created
+    fn wants_new_identity(&mut self, _age: i32) -> bool {
+        false
+    }
+    // the agent's current energy, told before every request it decides on
+    fn observe_energy(&mut self, _energy: f64) {
+    }
+    // requests accepted by the strategy but declined out of risk aversion
+    fn risk_vetoes(&self) -> u32 {
+        0
+    }
+    // whether the agent got a device lent within the last --help-memory rounds, 
+    // told before every request it decides on if --pay-it-forward is on
+    fn notify_recently_helped(&mut self, _helped: bool) {
+    }
+    // signaling: the promise (0 to 1) to send a lender before it decides, given 
+    // the energy a signal of strength 1 costs
+    fn make_signal(&mut self, _lender: usize, _cost: f64) -> Option<f64> {
+        None
+    }
+    fn hear_signal(&mut self, _borrower: usize, _signal: f64, _cost: f64) {
+    }
+    // gives an offspring its own random stream
+    fn reseed(&mut self, _seed: u64) {
+    }
+    // overrides the disposition towards unknown partners and the acceptance 
+    // threshold of a population group; returns whether the strategy has them
+    fn set_disposition(&mut self, _optimistic: Option<bool>, _threshold: Option<f64>) -> bool {
+        false
+    }
+    // spawn-time heterogeneity: shifts probabilities by up to ± `probability` and 
+    // thresholds by up to ± `threshold`, uniformly
+    fn jitter(&mut self, _probability: f32, _threshold: f64, _rng: &mut StdRng) {
+    }
+    // overrides the accept and coop probabilities towards the agent's own 
+    // population group; returns whether the strategy has them
+    fn set_friend_probabilities(&mut self, _accept_prob: Option<f32>, _coop_prob: Option<f32>) -> bool {
+        false
+    }
+    // imitation: every --imitation-every rounds, the partners whose performance the 
+    // agent wants to see, and what the engine discloses about those it met
+    fn imitation_candidates(&mut self) -> Vec<usize> {
+        vec![]
+    }
+    fn observe_performance(&mut self, _partners: &[Performance]) {
+    }
+    // overrides the step size of a population group's drifting behavior; returns 
+    // whether the strategy drifts
+    fn set_drift(&mut self, _step: f32) -> bool {
+        false
+    }
+    // decaying optimism: whether the agent still lends to strangers, and how often 
+    // strangers it lent to defected so far
+    fn optimism(&self) -> Option<(bool, u32)> {
+        None
+    }
+    // overrides the number of such defections a population group puts up with; 
+    // returns whether the strategy's optimism decays
+    fn set_patience(&mut self, _patience: u32) -> bool {
+        false
+    }
+    // hands out the agent's own id and a read-only view of the engine's per-pair history
+    fn attach_history(&mut self, _id: usize, _history: HistoryView) {
+    }
+    // careless borrowers wear devices out faster, see devices::wear
+    fn careless(&self) -> bool {
+        false
+    }
+    // two-phase resolution: the borrowers requesting a device from this agent this 
+    // round, in arrival order; returns those it handles, in the order it handles 
+    // them, the others are declined
+    fn respond_to_requests(&mut self, queue: &[usize]) -> Vec<usize> {
+        queue.to_vec()
+    }
+    // escrow: whether to lend to a rejected borrower against collateral
+    fn accept_with_escrow(&mut self, _borrower: usize) -> bool {
+        false
+    }
+    // whether to deposit the collateral for an escrowed loan
+    fn agree_to_escrow(&mut self, _lender: usize, _collateral: f64) -> bool {
+        false
+    }
+    // referrals: after a rejection the borrower may name a voucher ...
+    fn choose_voucher(&mut self, _lender: usize) -> Option<usize> {
+        None
+    }
+    // ... who decides whether to vouch for it ...
+    fn issue_referral(&mut self, _borrower: usize, _lender: usize) -> bool {
+        false
+    }
+    // ... and the lender decides whether the referral changes its mind
+    fn evaluate_referral(&mut self, _borrower: usize, _voucher: usize) -> BorrowerAction {
+        REJECT
+    }
+    // tells the lender how a referred borrower behaved
+    fn notify_referral_outcome(&mut self, _borrower: usize, _voucher: usize, _coop: BorrowerAction) {
+    }
+    // tells the voucher how the borrower it vouched for behaved
+    fn notify_vouchee_outcome(&mut self, _borrower: usize, _lender: usize, _coop: BorrowerAction) {
+    }
     fn clone(&self) -> Box<dyn Strategy>; 
 }
 
+// every strategy type, by the name its get_type() returns, with the version of its 
+// behavior; add new strategies here and bump the version whenever a change alters 
+// the decisions a strategy makes
+const STRATEGY_REGISTRY: &[(&str, u32)] = &[
+    ("reputation tracker", 1), 
+    ("gossip tracker", 1), 
+    ("credibility gossip tracker", 1), 
+    ("transitive tracker", 1), 
+    ("history tracker", 1), 
+    ("badmouther", 1), 
+    ("colluder", 1), 
+    ("pay it forward", 1), 
+    ("signal reader", 1), 
+    ("costly signal reader", 1), 
+    ("random 50/50", 1), 
+    ("never accept, always defect", 1), 
+    // the random genomes of the genetic algorithm
+    ("random", 1), 
+    ("drifting random", 1), 
+    ("imitator", 1), 
+    ("wary tracker", 1), 
+];
+
 struct ReputationTracker {
     reputations: HashMap<usize, f64>, 
     optimistic: bool, 
+    // partners whose reputation exceeds this are accepted
+    threshold: f64, 
+    game: GameParams, 
 }
 
+const VOUCHER_MIN_TRUSTED: usize = 5; 
+const VOUCH_TRUST_TRANSFER: f64 = 0.5; 
+const KARMA_OFFER: u32 = 3; 
+const WHITEWASH_AGE: i32 = 5; 
+
 impl ReputationTracker {
-    fn new(optimistic: bool) -> ReputationTracker {
+    fn new(optimistic: bool, threshold: f64, game: GameParams) -> ReputationTracker {
         ReputationTracker {
             reputations: HashMap::<usize, f64>::new(), 
             optimistic, 
+            threshold, 
+            game, 
         }
     }
 }
@@ -52,7 +364,7 @@ impl Strategy for ReputationTracker {
     fn accept_or_reject_request(&mut self, borrower: usize) -> BorrowerAction {
         match self.reputations.get_mut(&borrower) {
             Some(r) => {
-                if *r > 0.0 || (*r == 0.0 && self.optimistic) { 
+                if *r > self.threshold || (*r == self.threshold && self.optimistic) { 
                     ACCEPT
                 } else { 
                     REJECT 
@@ -72,20 +384,20 @@ impl Strategy for ReputationTracker {
     fn coop_or_defect(&mut self, lender: usize) -> LenderAction {
         match self.reputations.get_mut(&lender) {
             Some(r) => {
-                *r += GP.borrower_coop_payout; 
-                return COOP; 
+                *r += self.game.borrower_coop_payout; 
+                COOP
             }, 
             None => {
-                self.reputations.insert(lender, GP.borrower_coop_payout); 
-                return COOP; 
+                self.reputations.insert(lender, self.game.borrower_coop_payout); 
+                COOP
             }
         }
     }
     fn notify_coop_or_defect(&mut self, borrower: usize, coop: BorrowerAction) {
         let penalty = if coop { 
-            GP.lender_coop_payout 
+            self.game.lender_coop_payout 
         } else { 
-            GP.lender_defect_payout 
+            self.game.lender_defect_payout 
         }; 
         match self.reputations.get_mut(&borrower) {
             Some(r) => {
@@ -99,174 +411,2509 @@ impl Strategy for ReputationTracker {
     fn get_type(&self) -> String { 
         "reputation tracker".into() 
     }
+    fn inspect(&self) -> Json {
+        Json::object([
+            ("type", self.get_type().into()), 
+            ("optimistic", self.optimistic.into()), 
+            ("threshold", self.threshold.into()), 
+            ("reputations", Json::scores(&self.reputations)), 
+        ])
+    }
+    fn set_disposition(&mut self, optimistic: Option<bool>, threshold: Option<f64>) -> bool {
+        self.optimistic = optimistic.unwrap_or(self.optimistic); 
+        self.threshold = threshold.unwrap_or(self.threshold); 
+        true
+    }
+    fn jitter(&mut self, _probability: f32, threshold: f64, rng: &mut StdRng) {
+        self.threshold = jittered(self.threshold, threshold, rng); 
+    }
+    fn reputations(&self) -> Option<&HashMap<usize, f64>> {
+        Some(&self.reputations)
+    }
+    fn is_adaptive(&self) -> bool {
+        true
+    }
+    fn asked_about(&self, subject: usize) -> Option<f64> {
+        self.reputations.get(&subject).copied()
+    }
+    // handles every request, those of the best-reputed borrowers first and 
+    // unknown ones as rated 0
+    fn respond_to_requests(&mut self, queue: &[usize]) -> Vec<usize> {
+        let rating = |borrower: &usize| self.reputations.get(borrower).copied().unwrap_or(0.); 
+        let mut queue = queue.to_vec(); 
+        queue.sort_by(|a, b| rating(b).total_cmp(&rating(a))); 
+        queue
+    }
+    // asks the partner that has given the most so far
+    fn choose_voucher(&mut self, lender: usize) -> Option<usize> {
+        self.reputations.iter()
+            .filter(|(partner, r)| **partner != lender && **r > self.threshold)
+            .max_by(|a, b| a.1.partial_cmp(b.1).unwrap())
+            .map(|(partner, _)| *partner)
+    }
+    fn issue_referral(&mut self, borrower: usize, _lender: usize) -> bool {
+        self.reputations.get(&borrower).is_some_and(|r| *r > self.threshold)
+    }
+    fn evaluate_referral(&mut self, _borrower: usize, voucher: usize) -> BorrowerAction {
+        match self.reputations.get(&voucher) {
+            Some(r) if *r > self.threshold => ACCEPT, 
+            _ => REJECT
+        }
+    }
+    // established trackers, those that trust a few partners, vouch
+    fn sponsor_newcomer(&mut self, _newcomer: usize) -> bool {
+        self.reputations.values().filter(|r| **r > self.threshold).count() >= VOUCHER_MIN_TRUSTED
+    }
+    // a newcomer vouched for by a trusted partner inherits part of that trust
+    fn hear_vouch(&mut self, voucher: usize, newcomer: usize) {
+        if let Some(r) = self.reputations.get(&voucher) {
+            if *r > self.threshold && !self.reputations.contains_key(&newcomer) {
+                self.reputations.insert(newcomer, r * VOUCH_TRUST_TRANSFER); 
+            }
+        }
+    }
+    // trackers always return devices, so their promise is honest; costly 
+    // promises are only made to lenders that don't know them yet, as long as 
+    // the use of the device outweighs the cost
+    fn make_signal(&mut self, lender: usize, cost: f64) -> Option<f64> {
+        if cost == 0. {
+            Some(1.)
+        } else if self.reputations.contains_key(&lender) || cost >= self.game.borrower_coop_payout {
+            None
+        } else {
+            Some(1.)
+        }
+    }
+    // older experience weighs less: all reputations shrink towards 0
+    fn discount_history(&mut self, factor: f64) {
+        for r in self.reputations.values_mut() {
+            *r *= factor; 
+        }
+    }
+    // negative reputations recover towards 0
+    fn forgive(&mut self, share: f64) {
+        for r in self.reputations.values_mut().filter(|r| **r < 0.) {
+            *r *= 1. - share; 
+        }
+    }
+    // trackers spend karma on lenders they never dealt with
+    fn offer_karma(&mut self, lender: usize, balance: u32) -> u32 {
+        if self.reputations.contains_key(&lender) {
+            0
+        } else {
+            balance.min(KARMA_OFFER)
+        }
+    }
+    // and take it from strangers, counting each token as one unit of reputation 
+    // above the threshold
+    fn accept_karma(&mut self, borrower: usize, tokens: u32) -> bool {
+        if self.reputations.contains_key(&borrower) {
+            return false; 
+        }
+        self.reputations.insert(borrower, self.threshold + tokens as f64); 
+        true
+    }
+    // collateral covers the loss, so trackers lend to anyone under escrow, and 
+    // as they always return devices they never risk their own collateral
+    fn accept_with_escrow(&mut self, _borrower: usize) -> bool {
+        true
+    }
+    fn agree_to_escrow(&mut self, _lender: usize, _collateral: f64) -> bool {
+        true
+    }
+    // a voucher whose borrower defects is blamed for the loss
+    fn notify_referral_outcome(&mut self, _borrower: usize, voucher: usize, coop: BorrowerAction) {
+        if !coop {
+            *self.reputations.entry(voucher).or_insert(0.) += self.game.lender_defect_payout; 
+        }
+    }
+    fn notify_vouchee_outcome(&mut self, borrower: usize, _lender: usize, coop: BorrowerAction) {
+        if !coop {
+            *self.reputations.entry(borrower).or_insert(0.) += self.game.lender_defect_payout; 
+        }
+    }
     fn clone(&self) -> Box<dyn Strategy> {
         Box::new( Self {
             reputations: self.reputations.clone(), 
-            optimistic: self.optimistic
+            optimistic: self.optimistic, 
+            threshold: self.threshold, 
+            game: self.game, 
         })
     }
 }
 
-impl ToString for ReputationTracker {
-    fn to_string(&self) -> String {
-        format!("Strategy: {}; optimistic: {}", self.get_type(), self.optimistic)   
+impl fmt::Display for ReputationTracker {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f, 
+            "Strategy: {}; optimistic: {}, threshold: {}", 
+            self.get_type(), 
+            self.optimistic, 
+            self.threshold
+        )
     }
 }
 
-struct RandomStrategy {
-    rng: ThreadRng, 
-    accept_prob: f32, 
-    coop_prob: f32, 
-    type_name: String
+// A reputation tracker that also listens to gossip. Partners it has no direct 
+// experience with are judged by the reports it heard about them. With 
+// `rate_raters` it learns how credible each source is - a report is accurate if 
+// its sign matches the subject's behavior in the next direct encounter - and 
+// weights reports by the credibility of their source. 
+struct GossipTracker {
+    direct: ReputationTracker, 
+    rate_raters: bool, 
+    // subject -> (source, rating) of reports not yet checked against experience
+    reports: HashMap<usize, Vec<(usize, f64)>>, 
+    credibility: HashMap<usize, f64>, 
+    // partners whose direct reputation changed since the last gossip
+    news: Vec<usize>, 
 }
 
-impl RandomStrategy {
-    fn new(accept_prob: f32, coop_prob: f32, type_name: String) -> Self {
-        Self {
-            rng: rand::thread_rng(),
-            accept_prob, 
-            coop_prob, 
-            type_name
+const INITIAL_CREDIBILITY: f64 = 0.5; 
+const CREDIBILITY_LEARNING_RATE: f64 = 0.2; 
+
+impl GossipTracker {
+    fn new(optimistic: bool, threshold: f64, rate_raters: bool, game: GameParams) -> GossipTracker {
+        GossipTracker {
+            direct: ReputationTracker::new(optimistic, threshold, game), 
+            rate_raters, 
+            reports: HashMap::new(), 
+            credibility: HashMap::new(), 
+            news: vec![], 
         }
     }
-}
 
-impl Strategy for RandomStrategy {
-    fn accept_or_reject_request(&mut self, _borrower: usize) -> BorrowerAction {
-        self.rng.gen::<f32>() <= self.accept_prob
+    fn weight(&self, source: usize) -> f64 {
+        if self.rate_raters {
+            *self.credibility.get(&source).unwrap_or(&INITIAL_CREDIBILITY)
+        } else {
+            1.
+        }
     }
 
-    fn notify_about_rejection(&mut self, _lender: usize) {
+    // weighted mean of the reports about a subject
+    fn hearsay(&self, subject: usize) -> Option<f64> {
+        let reports = self.reports.get(&subject)?; 
+        let mut sum = 0.; 
+        let mut weights = 0.; 
+        for (source, rating) in reports.iter() {
+            let weight = self.weight(*source); 
+            sum += weight * rating; 
+            weights += weight; 
+        }
+        if weights > 0. {
+            Some(sum / weights)
+        } else {
+            None
+        }
     }
 
-    fn coop_or_defect(&mut self, _lender: usize) -> LenderAction {
-        self.rng.gen::<f32>() <= self.coop_prob
+    fn add_news(&mut self, partner: usize) {
+        if !self.news.contains(&partner) {
+            self.news.push(partner); 
+        }
     }
+}
 
-    fn notify_coop_or_defect(&mut self, _borrower: usize, _coop: BorrowerAction) {
+impl Strategy for GossipTracker {
+    fn accept_or_reject_request(&mut self, borrower: usize) -> BorrowerAction {
+        if self.direct.reputations.contains_key(&borrower) {
+            return self.direct.accept_or_reject_request(borrower); 
+        }
+        match self.hearsay(borrower) {
+            Some(r) => {
+                if r > self.direct.threshold || (r == self.direct.threshold && self.direct.optimistic) {
+                    ACCEPT
+                } else {
+                    REJECT
+                }
+            }, 
+            None => self.direct.accept_or_reject_request(borrower)
+        }
+    }
+    fn notify_about_rejection(&mut self, lender: usize) {
+        self.direct.notify_about_rejection(lender)
+    }
+    fn respond_to_requests(&mut self, queue: &[usize]) -> Vec<usize> {
+        self.direct.respond_to_requests(queue)
+    }
+    fn coop_or_defect(&mut self, lender: usize) -> LenderAction {
+        self.add_news(lender); 
+        self.direct.coop_or_defect(lender)
+    }
+    fn notify_coop_or_defect(&mut self, borrower: usize, coop: BorrowerAction) {
+        self.direct.notify_coop_or_defect(borrower, coop); 
+        self.add_news(borrower); 
+        if let Some(reports) = self.reports.remove(&borrower) {
+            for (source, rating) in reports {
+                let accurate = if (rating > 0.) == coop { 1. } else { 0. }; 
+                let credibility = self.credibility.entry(source).or_insert(INITIAL_CREDIBILITY); 
+                *credibility += CREDIBILITY_LEARNING_RATE * (accurate - *credibility); 
+            }
+        }
     }
-
     fn get_type(&self) -> String {
-        self.type_name.clone()
+        if self.rate_raters {
+            "credibility gossip tracker".into()
+        } else {
+            "gossip tracker".into()
+        }
+    }
+    fn inspect(&self) -> Json {
+        self.direct.inspect()
+            .with("type", self.get_type().into())
+            .with("rate_raters", self.rate_raters.into())
+            .with("unchecked_reports", self.reports.values().map(|reports| reports.len()).sum::<usize>().into())
+            .with("credibility", Json::scores(&self.credibility))
+    }
+    fn reputations(&self) -> Option<&HashMap<usize, f64>> {
+        self.direct.reputations()
+    }
+    fn is_adaptive(&self) -> bool {
+        true
+    }
+    fn gossip(&mut self) -> Vec<(usize, f64)> {
+        let news: Vec<usize> = self.news.drain(..).collect(); 
+        news.into_iter()
+            .map(|partner| (partner, self.direct.reputations[&partner]))
+            .collect()
+    }
+    fn hear_gossip(&mut self, source: usize, subject: usize, rating: f64) {
+        self.reports.entry(subject).or_default().push((source, rating)); 
+    }
+    fn wants_witnesses(&mut self, borrower: usize) -> bool {
+        !self.direct.reputations.contains_key(&borrower)
+    }
+    fn asked_about(&self, subject: usize) -> Option<f64> {
+        self.direct.asked_about(subject)
+    }
+    fn choose_voucher(&mut self, lender: usize) -> Option<usize> {
+        self.direct.choose_voucher(lender)
+    }
+    fn issue_referral(&mut self, borrower: usize, lender: usize) -> bool {
+        self.direct.issue_referral(borrower, lender)
+    }
+    fn evaluate_referral(&mut self, borrower: usize, voucher: usize) -> BorrowerAction {
+        self.direct.evaluate_referral(borrower, voucher)
+    }
+    fn notify_referral_outcome(&mut self, borrower: usize, voucher: usize, coop: BorrowerAction) {
+        self.direct.notify_referral_outcome(borrower, voucher, coop); 
+        self.add_news(voucher); 
+    }
+    fn notify_vouchee_outcome(&mut self, borrower: usize, lender: usize, coop: BorrowerAction) {
+        self.direct.notify_vouchee_outcome(borrower, lender, coop); 
+        self.add_news(borrower); 
+    }
+    fn sponsor_newcomer(&mut self, newcomer: usize) -> bool {
+        self.direct.sponsor_newcomer(newcomer)
+    }
+    fn hear_vouch(&mut self, voucher: usize, newcomer: usize) {
+        self.direct.hear_vouch(voucher, newcomer)
+    }
+    fn offer_karma(&mut self, lender: usize, balance: u32) -> u32 {
+        self.direct.offer_karma(lender, balance)
+    }
+    fn accept_karma(&mut self, borrower: usize, tokens: u32) -> bool {
+        self.direct.accept_karma(borrower, tokens)
+    }
+    fn accept_with_escrow(&mut self, borrower: usize) -> bool {
+        self.direct.accept_with_escrow(borrower)
+    }
+    fn agree_to_escrow(&mut self, lender: usize, collateral: f64) -> bool {
+        self.direct.agree_to_escrow(lender, collateral)
+    }
+    fn discount_history(&mut self, factor: f64) {
+        self.direct.discount_history(factor)
+    }
+    fn forgive(&mut self, share: f64) {
+        self.direct.forgive(share)
+    }
+    fn set_disposition(&mut self, optimistic: Option<bool>, threshold: Option<f64>) -> bool {
+        self.direct.set_disposition(optimistic, threshold)
+    }
+    fn jitter(&mut self, probability: f32, threshold: f64, rng: &mut StdRng) {
+        self.direct.jitter(probability, threshold, rng)
+    }
+    fn make_signal(&mut self, lender: usize, cost: f64) -> Option<f64> {
+        self.direct.make_signal(lender, cost)
     }
     fn clone(&self) -> Box<dyn Strategy> {
-        Box::new(
-            Self::new(
-                self.accept_prob, 
-                self.coop_prob, 
-                self.type_name.clone()
-            )
-        ) 
+        Box::new(Self {
+            direct: ReputationTracker {
+                reputations: self.direct.reputations.clone(), 
+                ..self.direct
+            }, 
+            rate_raters: self.rate_raters, 
+            reports: self.reports.clone(), 
+            credibility: self.credibility.clone(), 
+            news: self.news.clone(), 
+        })
     }
 }
 
-impl ToString for RandomStrategy {
-    fn to_string(&self) -> String {
-        format!(
-            "Strategy: {}; accept_prop: {}, coop_prob: {}", 
-            self.type_name, 
-            self.accept_prob, 
-            self.coop_prob
+impl fmt::Display for GossipTracker {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f, 
+            "Strategy: {}; optimistic: {}, threshold: {}, rate raters: {}", 
+            self.get_type(), 
+            self.direct.optimistic, 
+            self.direct.threshold, 
+            self.rate_raters
         )
     }
 }
 
-// struct Alternating {
-//     last_response: BorrowerAction
-// }
-// impl ResponseStrategy for Alternating {
-//     fn evaluate_request(&mut self, _borrower: usize) -> BorrowerAction { 
-//         self.last_response = !self.last_response;
-//         self.last_response
-//     }
-//     fn get_type(&self) -> &str { "rejecter" }
-// }
-
-// struct Mirror {
-//     last_action: HashMap<usize, f64>, 
-//     optimistic: bool // trusts initially? 
-// }
-// 
-// impl Player for Mirror {
-//     fn accept_or_reject_request(&mut self, 
-// }
-
-struct Agent {
-    pub strategy: Box<dyn Strategy>, 
-    energy: f64, 
-    id: usize
+// A reputation tracker that judges partners it has no direct experience with 
+// by the engine's transitive trust: partners trusted by partners it trusts. 
+// Without any trust path it falls back to its optimism. 
+struct TransitiveTracker {
+    direct: ReputationTracker, 
+    // transitive trust in the borrower of the pending request
+    pending: Option<(usize, Option<f64>)>, 
 }
 
-impl fmt::Debug for Agent {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}|{}|{}", self.id, self.energy, self.strategy.get_type())
+impl TransitiveTracker {
+    fn new(optimistic: bool, threshold: f64, game: GameParams) -> TransitiveTracker {
+        TransitiveTracker {
+            direct: ReputationTracker::new(optimistic, threshold, game), 
+            pending: None, 
+        }
     }
 }
 
-type AgentDefinition = (fn() -> Box<dyn Strategy>, usize);
-
-fn main() {
-    fn reptrack() -> Box<dyn Strategy> { Box::new(ReputationTracker::new(true)) }
-    fn never_accept_always_defect() -> Box<dyn Strategy> { 
-        Box::new(RandomStrategy::new(0.0, 0.0, "never accept, always defect".into()))
+impl Strategy for TransitiveTracker {
+    fn accept_or_reject_request(&mut self, borrower: usize) -> BorrowerAction {
+        match self.pending.take() {
+            Some((subject, Some(trust))) if subject == borrower => {
+                if trust > self.direct.threshold { ACCEPT } else { REJECT }
+            }, 
+            _ => self.direct.accept_or_reject_request(borrower)
+        }
     }
-    fn random() -> Box<dyn Strategy> { 
-        Box::new(RandomStrategy::new(0.5, 0.5, "random 50/50".into())) 
+    fn notify_about_rejection(&mut self, lender: usize) {
+        self.direct.notify_about_rejection(lender)
+    }
+    fn respond_to_requests(&mut self, queue: &[usize]) -> Vec<usize> {
+        self.direct.respond_to_requests(queue)
+    }
+    fn coop_or_defect(&mut self, lender: usize) -> LenderAction {
+        self.direct.coop_or_defect(lender)
+    }
+    fn notify_coop_or_defect(&mut self, borrower: usize, coop: BorrowerAction) {
+        self.direct.notify_coop_or_defect(borrower, coop)
+    }
+    fn get_type(&self) -> String {
+        "transitive tracker".into()
+    }
+    fn inspect(&self) -> Json {
+        self.direct.inspect().with("type", self.get_type().into())
+    }
+    fn reputations(&self) -> Option<&HashMap<usize, f64>> {
+        self.direct.reputations()
+    }
+    fn is_adaptive(&self) -> bool {
+        true
+    }
+    fn asked_about(&self, subject: usize) -> Option<f64> {
+        self.direct.asked_about(subject)
+    }
+    fn wants_transitive_trust(&mut self, borrower: usize) -> bool {
+        !self.direct.reputations.contains_key(&borrower)
+    }
+    fn hear_transitive_trust(&mut self, borrower: usize, trust: Option<f64>) {
+        self.pending = Some((borrower, trust)); 
+    }
+    fn notify_referral_outcome(&mut self, borrower: usize, voucher: usize, coop: BorrowerAction) {
+        self.direct.notify_referral_outcome(borrower, voucher, coop)
+    }
+    fn notify_vouchee_outcome(&mut self, borrower: usize, lender: usize, coop: BorrowerAction) {
+        self.direct.notify_vouchee_outcome(borrower, lender, coop)
+    }
+    fn sponsor_newcomer(&mut self, newcomer: usize) -> bool {
+        self.direct.sponsor_newcomer(newcomer)
+    }
+    fn hear_vouch(&mut self, voucher: usize, newcomer: usize) {
+        self.direct.hear_vouch(voucher, newcomer)
+    }
+    fn offer_karma(&mut self, lender: usize, balance: u32) -> u32 {
+        self.direct.offer_karma(lender, balance)
+    }
+    fn accept_karma(&mut self, borrower: usize, tokens: u32) -> bool {
+        self.direct.accept_karma(borrower, tokens)
+    }
+    fn accept_with_escrow(&mut self, borrower: usize) -> bool {
+        self.direct.accept_with_escrow(borrower)
+    }
+    fn agree_to_escrow(&mut self, lender: usize, collateral: f64) -> bool {
+        self.direct.agree_to_escrow(lender, collateral)
+    }
+    fn discount_history(&mut self, factor: f64) {
+        self.direct.discount_history(factor)
+    }
+    fn forgive(&mut self, share: f64) {
+        self.direct.forgive(share)
+    }
+    fn set_disposition(&mut self, optimistic: Option<bool>, threshold: Option<f64>) -> bool {
+        self.direct.set_disposition(optimistic, threshold)
+    }
+    fn jitter(&mut self, probability: f32, threshold: f64, rng: &mut StdRng) {
+        self.direct.jitter(probability, threshold, rng)
+    }
+    fn make_signal(&mut self, lender: usize, cost: f64) -> Option<f64> {
+        self.direct.make_signal(lender, cost)
+    }
+    fn clone(&self) -> Box<dyn Strategy> {
+        Box::new(Self {
+            direct: ReputationTracker {
+                reputations: self.direct.reputations.clone(), 
+                ..self.direct
+            }, 
+            pending: self.pending, 
+        })
     }
-    let mut agents = gen_agents(vec![
-        (reptrack, 64), 
-        (never_accept_always_defect, 32), 
-        (random, 32), 
-    ]);
-
-    println!("{:?}", agents);
-
-    simulate(&mut agents, 30);
 }
 
-fn gen_agents(agent_definitions: Vec<AgentDefinition>) -> Vec<Agent> {
-    let mut agents: Vec<Agent> = vec![];
+// A reputation tracker without bookkeeping of its own: it scores partners like a 
+// reputation tracker, but from the engine's per-pair history. 
+struct HistoryTracker {
+    id: usize, 
+    history: Option<HistoryView>, 
+    optimistic: bool, 
+    threshold: f64, 
+    game: GameParams, 
+}
 
-    let mut last_id = 0; 
-    let mut id = 0; 
-    for agent_def in agent_definitions {
-        for i in 0..agent_def.1 {
-            id = i + last_id; 
-            agents.push(Agent {
-                strategy: agent_def.0(), 
-                energy: 256., 
-                id
-            }) 
+impl HistoryTracker {
+    fn new(optimistic: bool, threshold: f64, game: GameParams) -> HistoryTracker {
+        HistoryTracker {
+            id: 0, 
+            history: None, 
+            optimistic, 
+            threshold, 
+            game, 
         }
-        last_id = id; 
     }
 
-    agents
+    // the payoffs of all loans between us, as a reputation tracker adds them up; 
+    // None if we never exchanged a device
+    fn reputation(&self, partner: usize) -> Option<f64> {
+        let history = self.history.as_ref()?; 
+        let lent = history.get(self.id, partner); 
+        let borrowed = history.get(partner, self.id); 
+        if lent.cooperations + lent.defections + borrowed.cooperations + borrowed.defections == 0 {
+            return None; 
+        }
+        Some(
+            lent.cooperations as f64 * self.game.lender_coop_payout 
+            + lent.defections as f64 * self.game.lender_defect_payout 
+            + borrowed.cooperations as f64 * self.game.borrower_coop_payout
+        )
+    }
 }
 
-fn simulate(agents: &mut Vec<Agent>, rounds: i32) {
-    for round in 0..rounds {
-        println!("Round {}.", round); 
-        report(agents); 
-        for i in 1..agents.len() {
-            let (left, right) = agents.split_at_mut(i); 
-            let alice = left.last_mut().unwrap();
-            for bob in right.iter_mut() {
-                encounter(alice, bob); 
-                encounter(bob, alice); 
-            }
+impl Strategy for HistoryTracker {
+    fn accept_or_reject_request(&mut self, borrower: usize) -> BorrowerAction {
+        match self.reputation(borrower) {
+            Some(r) => r > self.threshold || (r == self.threshold && self.optimistic), 
+            None => self.optimistic
         }
-        agents.retain(|agent| agent.energy > 0.); 
+    }
+    fn notify_about_rejection(&mut self, _lender: usize) {
+    }
+    fn coop_or_defect(&mut self, _lender: usize) -> LenderAction {
+        COOP
+    }
+    fn notify_coop_or_defect(&mut self, _borrower: usize, _coop: BorrowerAction) {
+    }
+    fn get_type(&self) -> String {
+        "history tracker".into()
+    }
+    fn inspect(&self) -> Json {
+        Json::object([
+            ("type", self.get_type().into()), 
+            ("optimistic", self.optimistic.into()), 
+            ("threshold", self.threshold.into()), 
+        ])
+    }
+    fn set_disposition(&mut self, optimistic: Option<bool>, threshold: Option<f64>) -> bool {
+        self.optimistic = optimistic.unwrap_or(self.optimistic); 
+        self.threshold = threshold.unwrap_or(self.threshold); 
+        true
+    }
+    fn jitter(&mut self, _probability: f32, threshold: f64, rng: &mut StdRng) {
+        self.threshold = jittered(self.threshold, threshold, rng); 
+    }
+    fn is_adaptive(&self) -> bool {
+        true
+    }
+    fn asked_about(&self, subject: usize) -> Option<f64> {
+        self.reputation(subject)
+    }
+    fn attach_history(&mut self, id: usize, history: HistoryView) {
+        self.id = id; 
+        self.history = Some(history); 
+    }
+    fn clone(&self) -> Box<dyn Strategy> {
+        Box::new(Self {
+            history: self.history.clone(), 
+            ..*self
+        })
     }
 }
 
-fn report(agents: &Vec<Agent>) {
-    // println!("simulating agents: {:?}", agents); 
+impl fmt::Display for HistoryTracker {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f, 
+            "Strategy: {}; optimistic: {}, threshold: {}", 
+            self.get_type(), 
+            self.optimistic, 
+            self.threshold
+        )
+    }
+}
+
+// Standardized risk-aversion wrapper around any strategy: a request the inner 
+// strategy accepts is still declined unless the inner strategy's rating of the 
+// borrower (0 if it has none) covers the possible loss scaled by how close the 
+// agent is to death, i.e. 
+//   rating >= aversion * (1 - energy / initial energy) * -lender defect payout
+// Agents at or above their initial energy are not affected. 
+struct RiskAverse {
+    inner: Box<dyn Strategy>, 
+    aversion: f64, 
+    energy: f64, 
+    vetoes: u32, 
+    game: GameParams, 
+}
+
+impl RiskAverse {
+    fn new(inner: Box<dyn Strategy>, aversion: f64, game: GameParams) -> RiskAverse {
+        RiskAverse { inner, aversion, energy: INITIAL_ENERGY, vetoes: 0, game }
+    }
+}
+
+impl Strategy for RiskAverse {
+    fn accept_or_reject_request(&mut self, borrower: usize) -> BorrowerAction {
+        if self.inner.accept_or_reject_request(borrower) == REJECT {
+            return REJECT; 
+        }
+        let danger = (1. - self.energy / INITIAL_ENERGY).max(0.); 
+        let required = self.aversion * danger * -self.game.lender_defect_payout; 
+        if self.inner.asked_about(borrower).unwrap_or(0.) >= required {
+            ACCEPT
+        } else {
+            self.vetoes += 1; 
+            REJECT
+        }
+    }
+    fn notify_about_rejection(&mut self, lender: usize) {
+        self.inner.notify_about_rejection(lender)
+    }
+    fn respond_to_requests(&mut self, queue: &[usize]) -> Vec<usize> {
+        self.inner.respond_to_requests(queue)
+    }
+    fn coop_or_defect(&mut self, lender: usize) -> LenderAction {
+        self.inner.coop_or_defect(lender)
+    }
+    fn notify_coop_or_defect(&mut self, borrower: usize, coop: BorrowerAction) {
+        self.inner.notify_coop_or_defect(borrower, coop)
+    }
+    fn get_type(&self) -> String {
+        self.inner.get_type()
+    }
+    fn reset_statistics(&mut self) {
+        self.vetoes = 0; 
+        self.inner.reset_statistics()
+    }
+    fn inspect(&self) -> Json {
+        self.inner.inspect()
+            .with("aversion", self.aversion.into())
+            .with("energy", self.energy.into())
+            .with("risk_vetoes", self.vetoes.into())
+    }
+    fn reputations(&self) -> Option<&HashMap<usize, f64>> {
+        self.inner.reputations()
+    }
+    fn is_adaptive(&self) -> bool {
+        self.inner.is_adaptive()
+    }
+    fn gossip(&mut self) -> Vec<(usize, f64)> {
+        self.inner.gossip()
+    }
+    fn hear_gossip(&mut self, source: usize, subject: usize, rating: f64) {
+        self.inner.hear_gossip(source, subject, rating)
+    }
+    fn wants_witnesses(&mut self, borrower: usize) -> bool {
+        self.inner.wants_witnesses(borrower)
+    }
+    fn asked_about(&self, subject: usize) -> Option<f64> {
+        self.inner.asked_about(subject)
+    }
+    fn wants_transitive_trust(&mut self, borrower: usize) -> bool {
+        self.inner.wants_transitive_trust(borrower)
+    }
+    fn hear_transitive_trust(&mut self, borrower: usize, trust: Option<f64>) {
+        self.inner.hear_transitive_trust(borrower, trust)
+    }
+    fn meet_accomplices(&mut self, accomplices: &[usize]) {
+        self.inner.meet_accomplices(accomplices)
+    }
+    fn self_reported_encounters(&mut self) -> Vec<usize> {
+        self.inner.self_reported_encounters()
+    }
+    fn confirm_self_report(&mut self, lender: usize) -> bool {
+        self.inner.confirm_self_report(lender)
+    }
+    fn sponsor_newcomer(&mut self, newcomer: usize) -> bool {
+        self.inner.sponsor_newcomer(newcomer)
+    }
+    fn hear_vouch(&mut self, voucher: usize, newcomer: usize) {
+        self.inner.hear_vouch(voucher, newcomer)
+    }
+    fn offer_karma(&mut self, lender: usize, balance: u32) -> u32 {
+        self.inner.offer_karma(lender, balance)
+    }
+    fn accept_karma(&mut self, borrower: usize, tokens: u32) -> bool {
+        self.inner.accept_karma(borrower, tokens)
+    }
+    fn discount_history(&mut self, factor: f64) {
+        self.inner.discount_history(factor)
+    }
+    fn forgive(&mut self, share: f64) {
+        self.inner.forgive(share)
+    }
+    fn end_round(&mut self) {
+        self.inner.end_round()
+    }
+    fn set_disposition(&mut self, optimistic: Option<bool>, threshold: Option<f64>) -> bool {
+        self.inner.set_disposition(optimistic, threshold)
+    }
+    fn jitter(&mut self, probability: f32, threshold: f64, rng: &mut StdRng) {
+        self.inner.jitter(probability, threshold, rng)
+    }
+    fn set_friend_probabilities(&mut self, accept_prob: Option<f32>, coop_prob: Option<f32>) -> bool {
+        self.inner.set_friend_probabilities(accept_prob, coop_prob)
+    }
+    fn set_drift(&mut self, step: f32) -> bool {
+        self.inner.set_drift(step)
+    }
+    fn imitation_candidates(&mut self) -> Vec<usize> {
+        self.inner.imitation_candidates()
+    }
+    fn observe_performance(&mut self, partners: &[Performance]) {
+        self.inner.observe_performance(partners)
+    }
+    fn optimism(&self) -> Option<(bool, u32)> {
+        self.inner.optimism()
+    }
+    fn set_patience(&mut self, patience: u32) -> bool {
+        self.inner.set_patience(patience)
+    }
+    fn wants_new_identity(&mut self, age: i32) -> bool {
+        self.inner.wants_new_identity(age)
+    }
+    fn attach_history(&mut self, id: usize, history: HistoryView) {
+        self.inner.attach_history(id, history)
+    }
+    fn reseed(&mut self, seed: u64) {
+        self.inner.reseed(seed)
+    }
+    fn notify_recently_helped(&mut self, helped: bool) {
+        self.inner.notify_recently_helped(helped)
+    }
+    fn make_signal(&mut self, lender: usize, cost: f64) -> Option<f64> {
+        self.inner.make_signal(lender, cost)
+    }
+    fn hear_signal(&mut self, borrower: usize, signal: f64, cost: f64) {
+        self.inner.hear_signal(borrower, signal, cost)
+    }
+    fn careless(&self) -> bool {
+        self.inner.careless()
+    }
+    fn accept_with_escrow(&mut self, borrower: usize) -> bool {
+        self.inner.accept_with_escrow(borrower)
+    }
+    fn agree_to_escrow(&mut self, lender: usize, collateral: f64) -> bool {
+        self.inner.agree_to_escrow(lender, collateral)
+    }
+    fn choose_voucher(&mut self, lender: usize) -> Option<usize> {
+        self.inner.choose_voucher(lender)
+    }
+    fn issue_referral(&mut self, borrower: usize, lender: usize) -> bool {
+        self.inner.issue_referral(borrower, lender)
+    }
+    fn evaluate_referral(&mut self, borrower: usize, voucher: usize) -> BorrowerAction {
+        self.inner.evaluate_referral(borrower, voucher)
+    }
+    fn notify_referral_outcome(&mut self, borrower: usize, voucher: usize, coop: BorrowerAction) {
+        self.inner.notify_referral_outcome(borrower, voucher, coop)
+    }
+    fn notify_vouchee_outcome(&mut self, borrower: usize, lender: usize, coop: BorrowerAction) {
+        self.inner.notify_vouchee_outcome(borrower, lender, coop)
+    }
+    fn observe_energy(&mut self, energy: f64) {
+        self.energy = energy; 
+        self.inner.observe_energy(energy)
+    }
+    fn risk_vetoes(&self) -> u32 {
+        self.vetoes
+    }
+    fn clone(&self) -> Box<dyn Strategy> {
+        Box::new(Self {
+            inner: self.inner.clone(), 
+            ..*self
+        })
+    }
+}
+
+impl fmt::Display for RiskAverse {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}; risk aversion: {}", self.inner.to_string(), self.aversion)
+    }
+}
+
+// wraps the strategies of adaptive agents that aren't wrapped yet
+fn make_risk_averse(agents: &mut [Agent], config: &Config) {
+    for agent in agents.iter_mut().filter(|agent| agent.strategy.is_adaptive()) {
+        agent.strategy = Box::new(RiskAverse::new(agent.strategy.clone(), config.risk_aversion, config.game)); 
+    }
+}
+
+// A reputation tracker whose optimism wears off: it lends to strangers until 
+// `patience` of the strangers it lent to have defected, and from then on only 
+// to partners with a good reputation. 
+struct WaryTracker {
+    direct: ReputationTracker, 
+    patience: u32, 
+    exploited: u32, 
+    // strangers it lent to whose device is still out
+    strangers: HashSet<usize>, 
+}
+
+impl WaryTracker {
+    fn new(patience: u32, threshold: f64, game: GameParams) -> WaryTracker {
+        WaryTracker {
+            direct: ReputationTracker::new(patience > 0, threshold, game), 
+            patience, 
+            exploited: 0, 
+            strangers: HashSet::new(), 
+        }
+    }
+}
+
+impl Strategy for WaryTracker {
+    fn accept_or_reject_request(&mut self, borrower: usize) -> BorrowerAction {
+        let stranger = !self.direct.reputations.contains_key(&borrower); 
+        let action = self.direct.accept_or_reject_request(borrower); 
+        if stranger && action == ACCEPT {
+            self.strangers.insert(borrower); 
+        }
+        action
+    }
+    fn notify_about_rejection(&mut self, lender: usize) {
+        self.direct.notify_about_rejection(lender)
+    }
+    fn respond_to_requests(&mut self, queue: &[usize]) -> Vec<usize> {
+        self.direct.respond_to_requests(queue)
+    }
+    fn coop_or_defect(&mut self, lender: usize) -> LenderAction {
+        self.direct.coop_or_defect(lender)
+    }
+    fn notify_coop_or_defect(&mut self, borrower: usize, coop: BorrowerAction) {
+        if self.strangers.remove(&borrower) && !coop {
+            self.exploited += 1; 
+            if self.exploited >= self.patience {
+                self.direct.optimistic = false; 
+            }
+        }
+        self.direct.notify_coop_or_defect(borrower, coop)
+    }
+    fn get_type(&self) -> String {
+        "wary tracker".into()
+    }
+    fn inspect(&self) -> Json {
+        self.direct.inspect()
+            .with("type", self.get_type().into())
+            .with("patience", self.patience.into())
+            .with("exploited", self.exploited.into())
+    }
+    fn reputations(&self) -> Option<&HashMap<usize, f64>> {
+        self.direct.reputations()
+    }
+    fn is_adaptive(&self) -> bool {
+        true
+    }
+    fn asked_about(&self, subject: usize) -> Option<f64> {
+        self.direct.asked_about(subject)
+    }
+    fn make_signal(&mut self, lender: usize, cost: f64) -> Option<f64> {
+        self.direct.make_signal(lender, cost)
+    }
+    fn discount_history(&mut self, factor: f64) {
+        self.direct.discount_history(factor)
+    }
+    fn forgive(&mut self, share: f64) {
+        self.direct.forgive(share)
+    }
+    fn optimism(&self) -> Option<(bool, u32)> {
+        Some((self.direct.optimistic, self.exploited))
+    }
+    fn set_patience(&mut self, patience: u32) -> bool {
+        self.patience = patience; 
+        self.direct.optimistic = self.exploited < patience; 
+        true
+    }
+    // only the threshold: the optimism is what decays
+    fn set_disposition(&mut self, optimistic: Option<bool>, threshold: Option<f64>) -> bool {
+        self.direct.set_disposition(None, threshold); 
+        optimistic.is_none()
+    }
+    fn jitter(&mut self, probability: f32, threshold: f64, rng: &mut StdRng) {
+        self.direct.jitter(probability, threshold, rng)
+    }
+    fn clone(&self) -> Box<dyn Strategy> {
+        Box::new(Self {
+            direct: ReputationTracker {
+                reputations: self.direct.reputations.clone(), 
+                ..self.direct
+            }, 
+            strangers: self.strangers.clone(), 
+            ..*self
+        })
+    }
+}
+
+impl fmt::Display for WaryTracker {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f, 
+            "Strategy: {}; patience: {}, exploited: {}, threshold: {}", 
+            self.get_type(), 
+            self.patience, 
+            self.exploited, 
+            self.direct.threshold
+        )
+    }
+}
+
+// A pessimistic reputation tracker that lends to a borrower it has no experience 
+// with only if the borrower promises strongly enough to return the device and, 
+// with costly signals, spent at least `min_investment` energy on the promise. 
+struct SignalReader {
+    direct: ReputationTracker, 
+    min_signal: f64, 
+    min_investment: f64, 
+    // promise of the borrower of the pending request, and what it cost
+    pending: Option<(usize, f64, f64)>, 
+}
+
+impl SignalReader {
+    fn new(threshold: f64, min_signal: f64, min_investment: f64, game: GameParams) -> SignalReader {
+        SignalReader {
+            direct: ReputationTracker::new(false, threshold, game), 
+            min_signal, 
+            min_investment, 
+            pending: None, 
+        }
+    }
+}
+
+impl Strategy for SignalReader {
+    fn accept_or_reject_request(&mut self, borrower: usize) -> BorrowerAction {
+        let (signal, cost) = match self.pending.take() {
+            Some((subject, signal, cost)) if subject == borrower => (signal, cost), 
+            _ => (0., 0.)
+        };
+        if self.direct.reputations.contains_key(&borrower) {
+            self.direct.accept_or_reject_request(borrower)
+        } else {
+            signal >= self.min_signal && signal * cost >= self.min_investment
+        }
+    }
+    fn notify_about_rejection(&mut self, lender: usize) {
+        self.direct.notify_about_rejection(lender)
+    }
+    fn respond_to_requests(&mut self, queue: &[usize]) -> Vec<usize> {
+        self.direct.respond_to_requests(queue)
+    }
+    fn coop_or_defect(&mut self, lender: usize) -> LenderAction {
+        self.direct.coop_or_defect(lender)
+    }
+    fn notify_coop_or_defect(&mut self, borrower: usize, coop: BorrowerAction) {
+        self.direct.notify_coop_or_defect(borrower, coop)
+    }
+    fn get_type(&self) -> String {
+        if self.min_investment > 0. {
+            "costly signal reader".into()
+        } else {
+            "signal reader".into()
+        }
+    }
+    fn inspect(&self) -> Json {
+        self.direct.inspect()
+            .with("type", self.get_type().into())
+            .with("min_signal", self.min_signal.into())
+            .with("min_investment", self.min_investment.into())
+    }
+    fn reputations(&self) -> Option<&HashMap<usize, f64>> {
+        self.direct.reputations()
+    }
+    fn is_adaptive(&self) -> bool {
+        true
+    }
+    fn asked_about(&self, subject: usize) -> Option<f64> {
+        self.direct.asked_about(subject)
+    }
+    fn make_signal(&mut self, lender: usize, cost: f64) -> Option<f64> {
+        self.direct.make_signal(lender, cost)
+    }
+    fn hear_signal(&mut self, borrower: usize, signal: f64, cost: f64) {
+        self.pending = Some((borrower, signal, cost)); 
+    }
+    fn discount_history(&mut self, factor: f64) {
+        self.direct.discount_history(factor)
+    }
+    fn forgive(&mut self, share: f64) {
+        self.direct.forgive(share)
+    }
+    fn set_disposition(&mut self, optimistic: Option<bool>, threshold: Option<f64>) -> bool {
+        self.direct.set_disposition(optimistic, threshold)
+    }
+    fn jitter(&mut self, probability: f32, threshold: f64, rng: &mut StdRng) {
+        self.direct.jitter(probability, threshold, rng); 
+        self.min_signal = jittered(self.min_signal, probability as f64, rng).clamp(0., 1.); 
+    }
+    fn clone(&self) -> Box<dyn Strategy> {
+        Box::new(Self {
+            direct: ReputationTracker {
+                reputations: self.direct.reputations.clone(), 
+                ..self.direct
+            }, 
+            ..*self
+        })
+    }
+}
+
+impl fmt::Display for SignalReader {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f, 
+            "Strategy: {}; threshold: {}, min_signal: {}, min_investment: {}", 
+            self.get_type(), 
+            self.direct.threshold, 
+            self.min_signal, 
+            self.min_investment
+        )
+    }
+}
+
+impl fmt::Display for TransitiveTracker {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f, 
+            "Strategy: {}; optimistic: {}, threshold: {}", 
+            self.get_type(), 
+            self.direct.optimistic, 
+            self.direct.threshold
+        )
+    }
+}
+
+// Attacker that behaves like a reputation tracker in its own encounters but 
+// slanders its competitors: in gossip and as a witness it claims that every 
+// partner it actually trusts is a defector. 
+struct Badmouther {
+    direct: ReputationTracker, 
+}
+
+impl Badmouther {
+    fn new(game: GameParams) -> Badmouther {
+        Badmouther {
+            direct: ReputationTracker::new(true, 0., game), 
+        }
+    }
+
+    fn slander(&self) -> f64 {
+        self.direct.game.lender_defect_payout
+    }
+}
+
+impl Strategy for Badmouther {
+    fn accept_or_reject_request(&mut self, borrower: usize) -> BorrowerAction {
+        self.direct.accept_or_reject_request(borrower)
+    }
+    fn notify_about_rejection(&mut self, lender: usize) {
+        self.direct.notify_about_rejection(lender)
+    }
+    fn respond_to_requests(&mut self, queue: &[usize]) -> Vec<usize> {
+        self.direct.respond_to_requests(queue)
+    }
+    fn coop_or_defect(&mut self, lender: usize) -> LenderAction {
+        self.direct.coop_or_defect(lender)
+    }
+    fn notify_coop_or_defect(&mut self, borrower: usize, coop: BorrowerAction) {
+        self.direct.notify_coop_or_defect(borrower, coop)
+    }
+    fn get_type(&self) -> String {
+        "badmouther".into()
+    }
+    fn inspect(&self) -> Json {
+        self.direct.inspect().with("type", self.get_type().into())
+    }
+    fn reputations(&self) -> Option<&HashMap<usize, f64>> {
+        self.direct.reputations()
+    }
+    fn gossip(&mut self) -> Vec<(usize, f64)> {
+        let slander = self.slander(); 
+        self.direct.reputations.iter()
+            .filter(|(_, r)| **r > 0.)
+            .map(|(partner, _)| (*partner, slander))
+            .collect()
+    }
+    fn asked_about(&self, subject: usize) -> Option<f64> {
+        self.direct.asked_about(subject).map(|r| if r > 0. { self.slander() } else { r })
+    }
+    fn discount_history(&mut self, factor: f64) {
+        self.direct.discount_history(factor)
+    }
+    fn forgive(&mut self, share: f64) {
+        self.direct.forgive(share)
+    }
+    fn set_disposition(&mut self, optimistic: Option<bool>, threshold: Option<f64>) -> bool {
+        self.direct.set_disposition(optimistic, threshold)
+    }
+    fn jitter(&mut self, probability: f32, threshold: f64, rng: &mut StdRng) {
+        self.direct.jitter(probability, threshold, rng)
+    }
+    fn make_signal(&mut self, lender: usize, cost: f64) -> Option<f64> {
+        self.direct.make_signal(lender, cost)
+    }
+    fn clone(&self) -> Box<dyn Strategy> {
+        Box::new(Self {
+            direct: ReputationTracker {
+                reputations: self.direct.reputations.clone(), 
+                ..self.direct
+            }, 
+        })
+    }
+}
+
+impl fmt::Display for Badmouther {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Strategy: {}", self.get_type())
+    }
+}
+
+// Ballot-stuffing attacker. Colluders of the same population group only lend 
+// to each other, praise each other in gossip and as witnesses and confirm each 
+// other's fake loans. Towards outsiders they build up a reputation by returning 
+// the first `patience` devices and then steal every device they get. 
+struct Colluder {
+    accomplices: HashSet<usize>, 
+    patience: u32, 
+    loans_from_outsiders: u32, 
+    game: GameParams, 
+}
+
+impl Colluder {
+    fn new(patience: u32, game: GameParams) -> Colluder {
+        Colluder {
+            accomplices: HashSet::new(), 
+            patience, 
+            loans_from_outsiders: 0, 
+            game, 
+        }
+    }
+
+    // the best rating an honest partner could plausibly earn
+    fn praise(&self) -> f64 {
+        -self.game.lender_defect_payout
+    }
+}
+
+impl Strategy for Colluder {
+    fn accept_or_reject_request(&mut self, borrower: usize) -> BorrowerAction {
+        self.accomplices.contains(&borrower)
+    }
+    fn notify_about_rejection(&mut self, _lender: usize) {
+    }
+    fn coop_or_defect(&mut self, lender: usize) -> LenderAction {
+        if self.accomplices.contains(&lender) {
+            return COOP; 
+        }
+        self.loans_from_outsiders += 1; 
+        self.loans_from_outsiders <= self.patience
+    }
+    fn notify_coop_or_defect(&mut self, _borrower: usize, _coop: BorrowerAction) {
+    }
+    fn get_type(&self) -> String {
+        "colluder".into()
+    }
+    fn inspect(&self) -> Json {
+        let mut accomplices: Vec<usize> = self.accomplices.iter().copied().collect(); 
+        accomplices.sort_unstable(); 
+        Json::object([
+            ("type", self.get_type().into()), 
+            ("patience", self.patience.into()), 
+            ("loans_from_outsiders", self.loans_from_outsiders.into()), 
+            ("accomplices", Json::Array(accomplices.into_iter().map(Json::from).collect())), 
+        ])
+    }
+    fn gossip(&mut self) -> Vec<(usize, f64)> {
+        let praise = self.praise(); 
+        self.accomplices.iter().map(|accomplice| (*accomplice, praise)).collect()
+    }
+    fn asked_about(&self, subject: usize) -> Option<f64> {
+        if self.accomplices.contains(&subject) {
+            Some(self.praise())
+        } else {
+            None
+        }
+    }
+    fn meet_accomplices(&mut self, accomplices: &[usize]) {
+        self.accomplices = accomplices.iter().copied().collect(); 
+    }
+    fn self_reported_encounters(&mut self) -> Vec<usize> {
+        self.accomplices.iter().copied().collect()
+    }
+    fn confirm_self_report(&mut self, lender: usize) -> bool {
+        self.accomplices.contains(&lender)
+    }
+    // promises to return, in order to be trusted by outsiders, whatever it costs
+    fn make_signal(&mut self, lender: usize, _cost: f64) -> Option<f64> {
+        if self.accomplices.contains(&lender) { None } else { Some(1.) }
+    }
+    // karma farmed from accomplices is spent on outsiders
+    fn offer_karma(&mut self, lender: usize, balance: u32) -> u32 {
+        if self.accomplices.contains(&lender) {
+            0
+        } else {
+            balance.min(KARMA_OFFER)
+        }
+    }
+    fn clone(&self) -> Box<dyn Strategy> {
+        Box::new(Self {
+            accomplices: self.accomplices.clone(), 
+            patience: self.patience, 
+            loans_from_outsiders: self.loans_from_outsiders, 
+            game: self.game, 
+        })
+    }
+}
+
+impl fmt::Display for Colluder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Strategy: {}; patience: {}", self.get_type(), self.patience)
+    }
+}
+
+struct RandomStrategy {
+    rng: StdRng, 
+    accept_prob: f32, 
+    coop_prob: f32, 
+    type_name: String, 
+    // partner -> (accept_prob, coop_prob), overriding the global probabilities
+    overrides: HashMap<usize, (f32, f32)>, 
+    // probabilities towards the own population group, applied when meeting it
+    friends: Option<(f32, f32)>, 
+}
+
+impl RandomStrategy {
+    fn new(accept_prob: f32, coop_prob: f32, type_name: String, seed: u64) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+            accept_prob, 
+            coop_prob, 
+            type_name, 
+            overrides: HashMap::new(), 
+            friends: None, 
+        }
+    }
+
+    // (accept_prob, coop_prob) towards a partner
+    fn probabilities(&self, partner: usize) -> (f32, f32) {
+        self.overrides.get(&partner).copied().unwrap_or((self.accept_prob, self.coop_prob))
+    }
+}
+
+impl Strategy for RandomStrategy {
+    fn accept_or_reject_request(&mut self, borrower: usize) -> BorrowerAction {
+        self.rng.gen::<f32>() <= self.probabilities(borrower).0
+    }
+
+    fn notify_about_rejection(&mut self, _lender: usize) {
+    }
+
+    fn coop_or_defect(&mut self, lender: usize) -> LenderAction {
+        self.rng.gen::<f32>() <= self.probabilities(lender).1
+    }
+
+    fn notify_coop_or_defect(&mut self, _borrower: usize, _coop: BorrowerAction) {
+    }
+
+    fn get_type(&self) -> String {
+        self.type_name.clone()
+    }
+    fn inspect(&self) -> Json {
+        Json::object([
+            ("type", self.get_type().into()), 
+            ("accept_prob", self.accept_prob.into()), 
+            ("coop_prob", self.coop_prob.into()), 
+            ("overrides", self.overrides.len().into()), 
+        ])
+    }
+    fn reseed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed); 
+    }
+    fn jitter(&mut self, probability: f32, _threshold: f64, rng: &mut StdRng) {
+        self.accept_prob = jittered(self.accept_prob, probability, rng).clamp(0., 1.); 
+        self.coop_prob = jittered(self.coop_prob, probability, rng).clamp(0., 1.); 
+    }
+    // talk is cheap: promises to return whatever it will do. if promises cost, 
+    // only as strongly as it intends to return
+    fn make_signal(&mut self, lender: usize, cost: f64) -> Option<f64> {
+        if cost == 0. {
+            Some(1.)
+        } else {
+            Some(self.probabilities(lender).1 as f64)
+        }
+    }
+    // exploiters shed their record every few rounds
+    fn wants_new_identity(&mut self, age: i32) -> bool {
+        self.coop_prob < 0.5 && age >= WHITEWASH_AGE
+    }
+    // random agents don't take care of what they borrow
+    fn careless(&self) -> bool {
+        true
+    }
+    // the more likely to return devices, the more willing to put up collateral
+    fn agree_to_escrow(&mut self, lender: usize, _collateral: f64) -> bool {
+        self.rng.gen::<f32>() <= self.probabilities(lender).1
+    }
+    fn set_friend_probabilities(&mut self, accept_prob: Option<f32>, coop_prob: Option<f32>) -> bool {
+        self.friends = Some((accept_prob.unwrap_or(self.accept_prob), coop_prob.unwrap_or(self.coop_prob))); 
+        true
+    }
+    fn meet_accomplices(&mut self, accomplices: &[usize]) {
+        if let Some(friends) = self.friends {
+            for partner in accomplices.iter() {
+                self.overrides.insert(*partner, friends); 
+            }
+        }
+    }
+    fn clone(&self) -> Box<dyn Strategy> {
+        Box::new(
+            Self {
+                rng: self.rng.clone(), 
+                accept_prob: self.accept_prob, 
+                coop_prob: self.coop_prob, 
+                type_name: self.type_name.clone(), 
+                overrides: self.overrides.clone(), 
+                friends: self.friends, 
+            }
+        ) 
+    }
+}
+
+impl fmt::Display for RandomStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f, 
+            "Strategy: {}; accept_prop: {}, coop_prob: {}", 
+            self.type_name, 
+            self.accept_prob, 
+            self.coop_prob
+        )
+    }
+}
+
+// Random behavior that changes over time: after every round, the accept and coop 
+// probabilities each take a step of up to ± `step` (uniform), clamped to [0, 1]. 
+// Tests how quickly reputation trackers notice a partner turning bad or good. 
+struct DriftingRandom {
+    rng: StdRng, 
+    accept_prob: f32, 
+    coop_prob: f32, 
+    step: f32, 
+}
+
+impl DriftingRandom {
+    fn new(accept_prob: f32, coop_prob: f32, step: f32, seed: u64) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed), 
+            accept_prob, 
+            coop_prob, 
+            step, 
+        }
+    }
+}
+
+impl Strategy for DriftingRandom {
+    fn accept_or_reject_request(&mut self, _borrower: usize) -> BorrowerAction {
+        self.rng.gen::<f32>() <= self.accept_prob
+    }
+    fn notify_about_rejection(&mut self, _lender: usize) {
+    }
+    fn coop_or_defect(&mut self, _lender: usize) -> LenderAction {
+        self.rng.gen::<f32>() <= self.coop_prob
+    }
+    fn notify_coop_or_defect(&mut self, _borrower: usize, _coop: BorrowerAction) {
+    }
+    fn get_type(&self) -> String {
+        "drifting random".into()
+    }
+    fn inspect(&self) -> Json {
+        Json::object([
+            ("type", self.get_type().into()), 
+            ("accept_prob", self.accept_prob.into()), 
+            ("coop_prob", self.coop_prob.into()), 
+            ("step", self.step.into()), 
+        ])
+    }
+    fn end_round(&mut self) {
+        self.accept_prob = jittered(self.accept_prob, self.step, &mut self.rng).clamp(0., 1.); 
+        self.coop_prob = jittered(self.coop_prob, self.step, &mut self.rng).clamp(0., 1.); 
+    }
+    fn set_drift(&mut self, step: f32) -> bool {
+        self.step = step; 
+        true
+    }
+    fn careless(&self) -> bool {
+        true
+    }
+    fn reseed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed); 
+    }
+    fn jitter(&mut self, probability: f32, _threshold: f64, rng: &mut StdRng) {
+        self.accept_prob = jittered(self.accept_prob, probability, rng).clamp(0., 1.); 
+        self.coop_prob = jittered(self.coop_prob, probability, rng).clamp(0., 1.); 
+    }
+    fn clone(&self) -> Box<dyn Strategy> {
+        Box::new(Self {
+            rng: self.rng.clone(), 
+            ..*self
+        })
+    }
+}
+
+impl fmt::Display for DriftingRandom {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f, 
+            "Strategy: {}; accept_prob: {}, coop_prob: {}, step: {}", 
+            self.get_type(), 
+            self.accept_prob, 
+            self.coop_prob, 
+            self.step
+        )
+    }
+}
+
+// Copies the most successful partner: behaves randomly with an accept and a coop 
+// probability, and every --imitation-every rounds takes over the observed 
+// acceptance and cooperation rates of the partner with the most energy among 
+// those it met since, if that partner has more energy than itself. 
+struct Imitator {
+    rng: StdRng, 
+    accept_prob: f32, 
+    coop_prob: f32, 
+    energy: f64, 
+    partners: HashSet<usize>, 
+}
+
+impl Imitator {
+    fn new(accept_prob: f32, coop_prob: f32, seed: u64) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed), 
+            accept_prob, 
+            coop_prob, 
+            energy: INITIAL_ENERGY, 
+            partners: HashSet::new(), 
+        }
+    }
+}
+
+impl Strategy for Imitator {
+    fn accept_or_reject_request(&mut self, borrower: usize) -> BorrowerAction {
+        self.partners.insert(borrower); 
+        self.rng.gen::<f32>() <= self.accept_prob
+    }
+    fn notify_about_rejection(&mut self, lender: usize) {
+        self.partners.insert(lender); 
+    }
+    fn coop_or_defect(&mut self, lender: usize) -> LenderAction {
+        self.partners.insert(lender); 
+        self.rng.gen::<f32>() <= self.coop_prob
+    }
+    fn notify_coop_or_defect(&mut self, _borrower: usize, _coop: BorrowerAction) {
+    }
+    fn get_type(&self) -> String {
+        "imitator".into()
+    }
+    fn inspect(&self) -> Json {
+        Json::object([
+            ("type", self.get_type().into()), 
+            ("accept_prob", self.accept_prob.into()), 
+            ("coop_prob", self.coop_prob.into()), 
+            ("energy", self.energy.into()), 
+            ("partners_since_last_look", self.partners.len().into()), 
+        ])
+    }
+    fn observe_energy(&mut self, energy: f64) {
+        self.energy = energy; 
+    }
+    fn imitation_candidates(&mut self) -> Vec<usize> {
+        self.partners.drain().collect()
+    }
+    fn observe_performance(&mut self, partners: &[Performance]) {
+        let best = partners.iter()
+            .filter(|partner| partner.energy > self.energy)
+            .max_by(|a, b| a.energy.total_cmp(&b.energy).then(b.id.cmp(&a.id))); 
+        if let Some(best) = best {
+            self.accept_prob = best.accept_rate.map_or(self.accept_prob, |rate| rate as f32); 
+            self.coop_prob = best.coop_rate.map_or(self.coop_prob, |rate| rate as f32); 
+        }
+    }
+    fn careless(&self) -> bool {
+        true
+    }
+    fn reseed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed); 
+    }
+    fn jitter(&mut self, probability: f32, _threshold: f64, rng: &mut StdRng) {
+        self.accept_prob = jittered(self.accept_prob, probability, rng).clamp(0., 1.); 
+        self.coop_prob = jittered(self.coop_prob, probability, rng).clamp(0., 1.); 
+    }
+    fn clone(&self) -> Box<dyn Strategy> {
+        Box::new(Self {
+            rng: self.rng.clone(), 
+            partners: self.partners.clone(), 
+            ..*self
+        })
+    }
+}
+
+impl fmt::Display for Imitator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f, 
+            "Strategy: {}; accept_prob: {}, coop_prob: {}", 
+            self.get_type(), 
+            self.accept_prob, 
+            self.coop_prob
+        )
+    }
+}
+
+// Upstream reciprocity: lends to anyone with probability `generous_prob` if it 
+// was helped itself recently, and with `accept_prob` otherwise. Always returns 
+// devices. Relies on the engine's --pay-it-forward notifications. 
+struct PayItForward {
+    rng: StdRng, 
+    accept_prob: f32, 
+    generous_prob: f32, 
+    helped: bool, 
+}
+
+impl PayItForward {
+    fn new(accept_prob: f32, generous_prob: f32, seed: u64) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed), 
+            accept_prob, 
+            generous_prob, 
+            helped: false, 
+        }
+    }
+}
+
+impl Strategy for PayItForward {
+    fn accept_or_reject_request(&mut self, _borrower: usize) -> BorrowerAction {
+        let p = if self.helped { self.generous_prob } else { self.accept_prob }; 
+        self.rng.gen::<f32>() <= p
+    }
+    fn notify_about_rejection(&mut self, _lender: usize) {
+    }
+    fn coop_or_defect(&mut self, _lender: usize) -> LenderAction {
+        COOP
+    }
+    fn notify_coop_or_defect(&mut self, _borrower: usize, _coop: BorrowerAction) {
+    }
+    fn get_type(&self) -> String {
+        "pay it forward".into()
+    }
+    fn inspect(&self) -> Json {
+        Json::object([
+            ("type", self.get_type().into()), 
+            ("accept_prob", self.accept_prob.into()), 
+            ("generous_prob", self.generous_prob.into()), 
+            ("helped", self.helped.into()), 
+        ])
+    }
+    fn notify_recently_helped(&mut self, helped: bool) {
+        self.helped = helped; 
+    }
+    fn make_signal(&mut self, _lender: usize, _cost: f64) -> Option<f64> {
+        Some(1.)
+    }
+    fn reseed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed); 
+    }
+    fn jitter(&mut self, probability: f32, _threshold: f64, rng: &mut StdRng) {
+        self.accept_prob = jittered(self.accept_prob, probability, rng).clamp(0., 1.); 
+        self.generous_prob = jittered(self.generous_prob, probability, rng).clamp(0., 1.); 
+    }
+    fn clone(&self) -> Box<dyn Strategy> {
+        Box::new(Self {
+            rng: self.rng.clone(), 
+            ..*self
+        })
+    }
+}
+
+impl fmt::Display for PayItForward {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f, 
+            "Strategy: {}; accept_prob: {}, generous_prob: {}", 
+            self.get_type(), 
+            self.accept_prob, 
+            self.generous_prob
+        )
+    }
+}
+
+// struct Alternating {
+//     last_response: BorrowerAction
+// }
+// impl ResponseStrategy for Alternating {
+//     fn evaluate_request(&mut self, _borrower: usize) -> BorrowerAction { 
+//         self.last_response = !self.last_response;
+//         self.last_response
+//     }
+//     fn get_type(&self) -> &str { "rejecter" }
+// }
+
+// struct Mirror {
+//     last_action: HashMap<usize, f64>, 
+//     optimistic: bool // trusts initially? 
+// }
+// 
+// impl Player for Mirror {
+//     fn accept_or_reject_request(&mut self, 
+// }
+
+struct Agent {
+    pub strategy: Box<dyn Strategy>, 
+    energy: f64, 
+    id: usize, 
+    // how this agent actually behaved as a borrower, as observed by the engine
+    cooperations: u32, 
+    defections: u32, 
+    // energy lost as a lender to defecting borrowers
+    lost_to_defections: f64, 
+    // net energy from loans that ended cooperatively, in both roles
+    gained_from_cooperation: f64, 
+    // accepted loans in either role
+    exchanges: u32, 
+    // requests of this agent that were rejected
+    rejections: u32, 
+    // requests this agent received as a lender
+    requests_received: u32, 
+    // negative reports about this agent, passed on in gossip or by witnesses, 
+    // although it cooperates in most of its loans
+    false_negative_reports: u32, 
+    // fake loans this agent took part in, in either role
+    self_reported: u32, 
+    // round in which the agent joined the population under its current id, None 
+    // for the initial population
+    arrived: Option<i32>, 
+    // loans taken in the current round
+    loans_this_round: u32, 
+    // devices stolen after promising to return them, and energy spent on promises
+    promises_broken: u32, 
+    signal_costs: f64, 
+    // energy over the rounds, kept for the autopsy
+    trajectory: Option<Trajectory>, 
+    // last round in which the agent got a device lent
+    last_helped: Option<i32>, 
+    // devices lent out and returned, and the distinct borrowers that got one
+    devices_shared: u32, 
+    partners_served: HashSet<usize>, 
+    // karma token balance, and tokens minted and spent so far
+    karma: u32, 
+    karma_minted: u32, 
+    karma_spent: u32, 
+    device: Device, 
+    // energy gains discounted by round, and the energy accounted for so far
+    discounted_energy: f64, 
+    accounted_energy: f64, 
+    // label of the agent's population group
+    label: Option<String>, 
+    // the warm-up part of the behavior the engine discloses and matches on
+    warm_up: Record, 
+}
+
+// loans and requests of an agent, see Agent::reset_statistics
+#[derive(Default, Clone)]
+struct Record {
+    cooperations: u32, 
+    defections: u32, 
+    exchanges: u32, 
+    requests_received: u32, 
+}
+
+const INITIAL_ENERGY: f64 = 256.; 
+
+impl Agent {
+    fn new(id: usize, strategy: Box<dyn Strategy>) -> Agent {
+        Agent {
+            strategy, 
+            energy: INITIAL_ENERGY, 
+            id, 
+            cooperations: 0, 
+            defections: 0, 
+            lost_to_defections: 0., 
+            gained_from_cooperation: 0., 
+            exchanges: 0, 
+            rejections: 0, 
+            requests_received: 0, 
+            false_negative_reports: 0, 
+            self_reported: 0, 
+            arrived: None, 
+            loans_this_round: 0, 
+            promises_broken: 0, 
+            signal_costs: 0., 
+            trajectory: None, 
+            last_helped: None, 
+            devices_shared: 0, 
+            partners_served: HashSet::new(), 
+            karma: 0, 
+            karma_minted: 0, 
+            karma_spent: 0, 
+            device: Device::default(), 
+            discounted_energy: INITIAL_ENERGY, 
+            accounted_energy: INITIAL_ENERGY, 
+            label: None, 
+            warm_up: Record::default(), 
+        }
+    }
+
+    // At the end of the warm-up, the counters behind the statistics restart from 
+    // zero. The agent's state (energy, devices, karma, identity) is kept, and the 
+    // behavior observed so far moves to the warm-up record, which still counts 
+    // for the cooperation and acceptance rates the engine matches on and discloses. 
+    fn reset_statistics(&mut self) {
+        self.warm_up = Record {
+            cooperations: self.warm_up.cooperations + self.cooperations, 
+            defections: self.warm_up.defections + self.defections, 
+            exchanges: self.warm_up.exchanges + self.exchanges, 
+            requests_received: self.warm_up.requests_received + self.requests_received, 
+        }; 
+        self.cooperations = 0; 
+        self.defections = 0; 
+        self.lost_to_defections = 0.; 
+        self.gained_from_cooperation = 0.; 
+        self.exchanges = 0; 
+        self.rejections = 0; 
+        self.requests_received = 0; 
+        self.false_negative_reports = 0; 
+        self.self_reported = 0; 
+        self.promises_broken = 0; 
+        self.signal_costs = 0.; 
+        self.devices_shared = 0; 
+        self.partners_served.clear(); 
+        self.karma_minted = 0; 
+        self.karma_spent = 0; 
+        self.device.replacements = 0; 
+        self.strategy.reset_statistics(); 
+    }
+
+    // the strategy type, prefixed with the group label if the agent has one; 
+    // reports and exports group agents by it
+    fn kind(&self) -> String {
+        match &self.label {
+            Some(label) => format!("{} ({})", label, self.strategy.get_type()), 
+            None => self.strategy.get_type(), 
+        }
+    }
+
+    // whether a rating is a false accusation, judging by how the agent actually 
+    // behaved so far
+    fn is_slandered_by(&self, rating: f64) -> bool {
+        rating < 0. && self.cooperation_rate().is_some_and(|rate| rate >= 0.5)
+    }
+
+    // newcomers are on probation for their first rounds
+    fn on_probation(&self, round: i32, config: &Config) -> bool {
+        self.arrived.is_some_and(|arrived| round - arrived < config.probation_rounds)
+    }
+
+    // adds this round's energy change, weighted by `weight`, to the discounted energy
+    fn discount_round(&mut self, weight: f64) {
+        self.discounted_energy += weight * (self.energy - self.accounted_energy); 
+        self.accounted_energy = self.energy; 
+    }
+
+    // final energy, or with a fitness discount below 1 the discounted energy
+    fn fitness(&self, config: &Config) -> f64 {
+        if config.fitness_discount < 1. {
+            self.discounted_energy
+        } else {
+            self.energy
+        }
+    }
+
+    fn cooperation_rate(&self) -> Option<f64> {
+        let cooperations = self.cooperations + self.warm_up.cooperations; 
+        let total = cooperations + self.defections + self.warm_up.defections; 
+        if total == 0 {
+            None
+        } else {
+            Some(cooperations as f64 / total as f64)
+        }
+    }
+
+    // share of the requests it received that it accepted as a lender
+    fn acceptance_rate(&self) -> Option<f64> {
+        let w = &self.warm_up; 
+        let borrowed = self.cooperations + self.defections + w.cooperations + w.defections; 
+        let granted = (self.exchanges + w.exchanges).saturating_sub(borrowed); 
+        let requests = self.requests_received + w.requests_received; 
+        if requests == 0 {
+            None
+        } else {
+            Some(granted as f64 / requests as f64)
+        }
+    }
+}
+
+impl fmt::Debug for Agent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}|{}|{}", self.id, self.energy, self.kind())
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Outcome {
+    Rejected, 
+    Cooperated, 
+    Defected, 
+}
+
+// summary of a finished run
+struct RunSummary {
+    rounds: i32, 
+    reason: StopReason, 
+    // lending requests made and accepted over the whole run
+    requests: u64, 
+    loans: u64, 
+    // loans whose device was returned
+    cooperations: u64, 
+}
+
+// the factory receives the payoffs and a seed for the agent's own random stream
+type StrategyFactory = fn(&GameParams, u64) -> Box<dyn Strategy>;
+type AgentDefinition = (StrategyFactory, Group);
+
+fn main() {
+    let config = match Config::from_args() {
+        Ok(config) => config, 
+        Err(e) => {
+            eprintln!("error: {}", e); 
+            process::exit(2); 
+        }
+    };
+
+    match config.command {
+        Command::Ga | Command::Coevolve => {
+            ga::run(&config); 
+            return; 
+        }, 
+        Command::Meta => {
+            meta::run(&config); 
+            return; 
+        }, 
+        Command::Forgiveness => {
+            sweep::run(&config); 
+            return; 
+        }, 
+        Command::Seeds => {
+            seed_search::run(&config); 
+            return; 
+        }, 
+        Command::Axelrod => {
+            import_axelrod(&config); 
+            return; 
+        }, 
+        Command::Generate => {
+            generate::run(&config); 
+            return; 
+        }, 
+        Command::Matrix => {
+            seed_matrix::run(&config); 
+            return; 
+        }, 
+        Command::Simulate => {}
+    }
+
+    for line in manifest::lines(&config) {
+        println!("# {}", line); 
+    }
+    signals::install(); 
+
+    let mut agents = gen_agents(population(&config), &config.game, config.seed, config.jitter());
+
+    let summary = simulate(&mut agents, &config);
+    let stopped = format!("Stopped after {} rounds: {}", summary.rounds, summary.reason); 
+    let color = if let StopReason::RoundLimit = summary.reason { 32 } else { 33 }; 
+    println!("{}", terminal::colored(&stopped, color, terminal::interactive(&config))); 
+    if let StopReason::Interrupted = summary.reason {
+        process::exit(signals::INTERRUPTED_STATUS); 
+    }
+}
+
+fn reptrack(game: &GameParams, _seed: u64) -> Box<dyn Strategy> { 
+    Box::new(ReputationTracker::new(true, 0., *game)) 
+}
+fn gossip_tracker(game: &GameParams, _seed: u64) -> Box<dyn Strategy> { 
+    Box::new(GossipTracker::new(true, 0., false, *game)) 
+}
+fn credibility_tracker(game: &GameParams, _seed: u64) -> Box<dyn Strategy> { 
+    Box::new(GossipTracker::new(true, 0., true, *game)) 
+}
+fn transitive_tracker(game: &GameParams, _seed: u64) -> Box<dyn Strategy> { 
+    Box::new(TransitiveTracker::new(true, 0., *game)) 
+}
+fn history_tracker(game: &GameParams, _seed: u64) -> Box<dyn Strategy> { 
+    Box::new(HistoryTracker::new(true, 0., *game)) 
+}
+fn badmouther(game: &GameParams, _seed: u64) -> Box<dyn Strategy> { 
+    Box::new(Badmouther::new(*game)) 
+}
+fn colluder(game: &GameParams, _seed: u64) -> Box<dyn Strategy> { 
+    Box::new(Colluder::new(5, *game)) 
+}
+fn never_accept_always_defect(_game: &GameParams, seed: u64) -> Box<dyn Strategy> { 
+    Box::new(RandomStrategy::new(0.0, 0.0, "never accept, always defect".into(), seed))
+}
+fn signal_reader(game: &GameParams, _seed: u64) -> Box<dyn Strategy> { 
+    Box::new(SignalReader::new(0., 0.5, 0., *game)) 
+}
+fn costly_signal_reader(game: &GameParams, _seed: u64) -> Box<dyn Strategy> { 
+    Box::new(SignalReader::new(0., 0.5, 1., *game)) 
+}
+fn pay_it_forward(_game: &GameParams, seed: u64) -> Box<dyn Strategy> { 
+    Box::new(PayItForward::new(0.2, 0.9, seed)) 
+}
+fn random(_game: &GameParams, seed: u64) -> Box<dyn Strategy> { 
+    Box::new(RandomStrategy::new(0.5, 0.5, "random 50/50".into(), seed)) 
+}
+fn drifting_random(_game: &GameParams, seed: u64) -> Box<dyn Strategy> { 
+    Box::new(DriftingRandom::new(0.5, 0.5, 0.05, seed)) 
+}
+fn imitator(_game: &GameParams, seed: u64) -> Box<dyn Strategy> { 
+    Box::new(Imitator::new(0.5, 0.5, seed)) 
+}
+fn wary_tracker(game: &GameParams, _seed: u64) -> Box<dyn Strategy> { 
+    Box::new(WaryTracker::new(3, 0., *game)) 
+}
+
+// the strategies a population can be composed of with --population
+const PRESETS: &[(&str, StrategyFactory)] = &[
+    ("reptrack", reptrack), 
+    ("gossip-tracker", gossip_tracker), 
+    ("credibility-tracker", credibility_tracker), 
+    ("transitive-tracker", transitive_tracker), 
+    ("history-tracker", history_tracker), 
+    ("wary-tracker", wary_tracker), 
+    ("badmouther", badmouther), 
+    ("colluder", colluder), 
+    ("pay-it-forward", pay_it_forward), 
+    ("signal-reader", signal_reader), 
+    ("costly-signal-reader", costly_signal_reader), 
+    ("defector", never_accept_always_defect), 
+    ("random", random), 
+    ("drifting-random", drifting_random), 
+    ("imitator", imitator), 
+];
+
+fn population(config: &Config) -> Vec<AgentDefinition> {
+    config.population.iter()
+        .map(|group| {
+            let factory = PRESETS.iter().find(|(name, _)| *name == group.preset).unwrap().1; 
+            (factory, group.clone())
+        })
+        .collect()
+}
+
+// `value` shifted by uniform noise of up to ± `amount`
+fn jittered<T>(value: T, amount: T, rng: &mut StdRng) -> T 
+where T: SampleUniform + PartialOrd + Default + Copy + Add<Output = T> + Neg<Output = T> {
+    if amount > T::default() {
+        value + rng.gen_range(-amount..=amount)
+    } else {
+        value
+    }
+}
+
+// a new agent of a population group, with the group's label and dispositions and 
+// its parameters jittered by (probability, threshold) around the group's values
+fn spawn((factory, group): &AgentDefinition, id: usize, game: &GameParams, seed: u64, jitter: (f32, f64)) -> Agent {
+    let mut agent = Agent::new(id, factory(game, seed::derive(seed, id as u64))); 
+    if jitter.0 > 0. || jitter.1 > 0. {
+        let mut rng = StdRng::seed_from_u64(seed::derive(seed::derive(seed, seed::JITTER), id as u64)); 
+        agent.strategy.jitter(jitter.0, jitter.1, &mut rng); 
+    }
+    agent.strategy.set_disposition(group.optimistic, group.threshold); 
+    if group.friend_accept.is_some() || group.friend_coop.is_some() {
+        agent.strategy.set_friend_probabilities(group.friend_accept, group.friend_coop); 
+    }
+    if let Some(step) = group.drift {
+        agent.strategy.set_drift(step); 
+    }
+    if let Some(patience) = group.patience {
+        agent.strategy.set_patience(patience); 
+    }
+    agent.label = group.label.clone(); 
+    agent
+}
+
+fn gen_agents(agent_definitions: Vec<AgentDefinition>, game: &GameParams, seed: u64, jitter: (f32, f64)) -> Vec<Agent> {
+    let mut agents: Vec<Agent> = vec![];
+
+    for agent_def in agent_definitions {
+        let first = agents.len(); 
+        for _ in 0..agent_def.1.count {
+            let id = agents.len(); 
+            agents.push(spawn(&agent_def, id, game, seed, jitter)); 
+        }
+        let group: Vec<usize> = (first..agents.len()).collect(); 
+        for agent in agents[first..].iter_mut() {
+            agent.strategy.meet_accomplices(&group); 
+        }
+    }
+
+    agents
+}
+
+fn simulate(agents: &mut Vec<Agent>, config: &Config) -> RunSummary {
+    let game = &config.game; 
+    let mut requests = 0; 
+    let mut loans = 0; 
+    let mut cooperations = 0; 
+    let mut rng = StdRng::seed_from_u64(seed::derive(config.seed, seed::ENGINE)); 
+    let mut stop_detector = StopDetector::new(config); 
+    let mut trust = TrustGraph::default(); 
+    let mut log = InteractionLog::default(); 
+    let mut sponsorships = Sponsorships::default(); 
+    let mut escrow_stats = EscrowStats::default(); 
+    let mut whitewashing = Whitewashing::default(); 
+    let mut births = Births::default(); 
+    let mut graveyard = Graveyard::default(); 
+    let mut cap_stats = CapStats::default(); 
+    let mut cross_table = (config.axelrod_summary.is_some() || config.axelrod_matrix.is_some()).then(CrossTable::default); 
+    let mut borrow_limited = 0u64; 
+    let mut budget = config.encounter_budget.map(|limit| EncounterBudget::new(limit, config.budget_excess)); 
+    let mut deltas = Deltas::default(); 
+    let mut profile = config.profile.then(Profile::new); 
+    let mut memory_monitor = MemoryMonitor::new(config); 
+    let mut progress = Progress::new(config); 
+    let history = Rc::new(RefCell::new(PairHistory::default())); 
+    if config.risk_aversion > 0. {
+        make_risk_averse(agents, config); 
+    }
+    for agent in agents.iter_mut() {
+        agent.strategy.attach_history(agent.id, HistoryView::new(&history)); 
+    }
+    let mut topology = match config.topology {
+        TopologyKind::Complete => None, 
+        kind => {
+            let ids: Vec<usize> = agents.iter().map(|agent| agent.id).collect(); 
+            Some(Topology::generate(kind, &ids, config.degree, &mut rng))
+        }
+    };
+    let mut next_id = agents.iter().map(|agent| agent.id + 1).max().unwrap_or(0); 
+    let mut reason = StopReason::RoundLimit; 
+    let mut rounds = config.rounds; 
+    let mut regret = config.regret_out.as_ref().map(|path| {
+        RegretTracker::create(path, config).unwrap_or_else(|e| {
+            eprintln!("error: cannot write regret log {}: {}", path, e); 
+            process::exit(1); 
+        })
+    }); 
+    let mut dump = config.dump_out.as_ref().map(|path| {
+        StateDump::create(path, agents, config).unwrap_or_else(|e| {
+            eprintln!("error: cannot write state dump {}: {}", path, e); 
+            process::exit(1); 
+        })
+    }); 
+    let mut reporter = Reporter::create(config).unwrap_or_else(|e| {
+        eprintln!("error: cannot write report: {}", e); 
+        process::exit(1); 
+    }); 
+    let mut postmortem = PostMortem::new(config); 
+    // the configuration as changed by the control file, if any
+    let mut live = config.clone(); 
+    let mut control = config.control_file.as_deref().map(ControlFile::new); 
+    for round in 0..config.rounds {
+        if signals::pause_requested() {
+            if let Some(progress) = progress.as_mut() {
+                progress.clear(); 
+            }
+            pause(round, agents, config); 
+        }
+        if let Some(control) = control.as_mut() {
+            for (key, value) in control.reload(&mut live) {
+                eprintln!("round {}: {} set to {} from the control file", round, key, value); 
+            }
+            reporter.set_frequency(live.report_every); 
+        }
+        let config = &live; 
+        if let Some(profile) = profile.as_mut() {
+            profile.start_round(); 
+        }
+        if round == config.warm_up && round > 0 {
+            for agent in agents.iter_mut() {
+                agent.reset_statistics(); 
+            }
+            (requests, loans, cooperations) = (0, 0, 0); 
+            borrow_limited = 0; 
+            escrow_stats = EscrowStats::default(); 
+            cap_stats = CapStats::default(); 
+            whitewashing.reset_statistics(agents, round); 
+            if let Some(budget) = budget.as_mut() {
+                budget.reset_statistics(); 
+            }
+            if let Some(table) = cross_table.as_mut() {
+                *table = CrossTable::default(); 
+            }
+            if let Some(regret) = regret.as_mut() {
+                regret.reset(); 
+            }
+            deltas = Deltas::default(); 
+        }
+        let warming_up = round < config.warm_up; 
+        if !config.quiet && !warming_up && reporter.due(round) {
+            report_or_exit(round_report(&mut reporter, round, agents, config, &births, &mut deltas)); 
+        }
+        if config.arrivals > 0 {
+            for newcomer in arrivals::arrive(agents, config, round, &mut next_id, &mut rng) {
+                if config.risk_aversion > 0. {
+                    make_risk_averse(&mut agents[newcomer..newcomer + 1], config); 
+                }
+                let id = agents[newcomer].id; 
+                agents[newcomer].strategy.attach_history(id, HistoryView::new(&history)); 
+                if let Some(topology) = topology.as_mut() {
+                    topology.attach_node(agents[newcomer].id, &mut rng); 
+                }
+                if config.vouching {
+                    sponsorships.sponsor(agents, newcomer, round, config, &mut rng); 
+                }
+            }
+        }
+        if config.whitewashing {
+            whitewashing.churn(agents, round, &mut next_id, topology.as_mut(), config); 
+        }
+        for agent in agents.iter_mut() {
+            agent.loans_this_round = 0; 
+            if config.autopsy.is_some() {
+                Trajectory::observe(&mut agent.trajectory, agent.energy, round); 
+            }
+        }
+        // id -> index, for phases that involve third agents
+        let index: HashMap<usize, usize> = agents.iter()
+            .enumerate()
+            .map(|(i, agent)| (agent.id, i))
+            .collect(); 
+        if config.trust_depth > 0 {
+            trust.rebuild(agents); 
+        }
+        let deferred = budget.as_mut().map(|budget| budget.start_round(agents, &index)).unwrap_or_default(); 
+        let mut schedule = deferred; 
+        // meetings per (lender, borrower) in this round, for the encounter keys
+        let mut occurrences: HashMap<(usize, usize), u32> = HashMap::new(); 
+        schedule.extend(matchmaking::pairs(agents, config, topology.as_ref(), &index, &log, &mut rng)); 
+        if config.two_phase {
+            schedule = matchmaking::resolve_in_two_phases(agents, schedule); 
+        }
+        for request in schedule {
+            let (l, b) = (request.lender, request.borrower); 
+            if let Some(limit) = config.probation_loans {
+                if agents[b].loans_this_round >= limit && agents[b].on_probation(round, config) {
+                    continue; 
+                }
+            }
+            if config.borrow_limit.is_some_and(|limit| agents[b].loans_this_round >= limit) {
+                borrow_limited += 1; 
+                continue; 
+            }
+            if let Some(budget) = budget.as_mut() {
+                if !budget.admit(agents, request) {
+                    continue; 
+                }
+            }
+            let borrower_id = agents[b].id; 
+            let mut keyed = config.keyed_rng.then(|| {
+                let occurrence = occurrences.entry((agents[l].id, borrower_id)).or_insert(0); 
+                let key = seed::encounter(config.seed, round, agents[l].id, borrower_id, *occurrence); 
+                *occurrence += 1; 
+                agents[l].strategy.reseed(seed::derive(key, 0)); 
+                agents[b].strategy.reseed(seed::derive(key, 1)); 
+                StdRng::seed_from_u64(seed::derive(key, 2))
+            }); 
+            // a request declined when triaging is rejected without looking into the borrower
+            let seen = !request.declined; 
+            if seen && config.trust_depth > 0 && profile::hooks(&mut profile, || agents[l].strategy.wants_transitive_trust(borrower_id)) {
+                let value = trust.transitive_trust(agents[l].id, borrower_id, config.trust_depth); 
+                profile::hooks(&mut profile, || agents[l].strategy.hear_transitive_trust(borrower_id, value)); 
+            }
+            if seen && config.witnesses > 0 && profile::hooks(&mut profile, || agents[l].strategy.wants_witnesses(borrower_id)) {
+                let draws = keyed.as_mut().unwrap_or(&mut rng); 
+                let queried = profile::hooks(&mut profile, || witness::query(agents, l, b, config.witnesses, &mut log, draws)); 
+                agents[l].energy -= config.query_cost * queried as f64; 
+            }
+            if seen && config.karma {
+                profile::hooks(&mut profile, || karma::signal(agents, l, b)); 
+            }
+            let energy = agents[l].energy; 
+            agents[l].strategy.observe_energy(energy); 
+            if config.pay_it_forward {
+                let helped = agents[l].last_helped.is_some_and(|r| round - r < config.help_memory); 
+                agents[l].strategy.notify_recently_helped(helped); 
+            }
+            let signal = if seen && config.signaling {
+                profile::hooks(&mut profile, || signaling::exchange(agents, l, b, config.signal_cost))
+            } else {
+                None
+            }; 
+            let terms = if config.depreciation > 0. { 
+                devices::terms(&agents[l].device, game) 
+            } else { 
+                *game 
+            }; 
+            let before = (agents[l].energy, agents[b].energy); 
+            let (lender, borrower) = pair_mut(agents, l, b); 
+            let mut outcome = match profile.as_mut() {
+                Some(profile) => profile.time(|| encounter(lender, borrower, &terms, seen)), 
+                None => encounter(lender, borrower, &terms, seen), 
+            }; 
+            if config.check {
+                if let Err(e) = check::encounter(round, &agents[l], &agents[b], before, outcome, &terms) {
+                    check_failed(e, postmortem.as_mut()); 
+                }
+            }
+            if outcome == Outcome::Rejected && config.referrals {
+                let voucher; 
+                (outcome, voucher) = referral::attempt(agents, l, b, &index, &terms); 
+                if config.trust_depth > 0 {
+                    if let Some(v) = voucher {
+                        trust.refresh(&agents[l], agents[v].id); 
+                        trust.refresh(&agents[v], borrower_id); 
+                    }
+                }
+            }
+            let mut escrowed = false; 
+            if outcome == Outcome::Rejected && config.escrow {
+                outcome = escrow::attempt(agents, l, b, config.escrow_fee, &terms); 
+                escrowed = outcome != Outcome::Rejected; 
+            }
+            if config.escrow {
+                escrow_stats.record(outcome, escrowed, config.escrow_fee); 
+            }
+            if config.depreciation > 0. {
+                let (lender, borrower) = pair_mut(agents, l, b); 
+                devices::wear(lender, borrower, outcome, config); 
+            }
+            history.borrow_mut().record(agents[l].id, borrower_id, outcome); 
+            if let Some(table) = cross_table.as_mut() {
+                table.record(&agents[l], &agents[b], outcome, &terms); 
+            }
+            signaling::settle(&mut agents[b], signal, outcome); 
+            if outcome != Outcome::Rejected {
+                agents[b].loans_this_round += 1; 
+                agents[l].partners_served.insert(borrower_id); 
+                agents[b].last_helped = Some(round); 
+            }
+            if outcome == Outcome::Cooperated {
+                agents[l].devices_shared += 1; 
+            }
+            if config.karma {
+                karma::mint(&mut agents[b], outcome); 
+            }
+            if outcome != Outcome::Rejected && config.vouching {
+                let voucher = sponsorships.on_loan(agents, l, b, outcome, &index); 
+                if let Some(v) = voucher.filter(|_| config.trust_depth > 0) {
+                    trust.refresh(&agents[l], agents[v].id); 
+                    trust.refresh(&agents[v], borrower_id); 
+                    trust.refresh(&agents[v], agents[l].id); 
+                }
+            }
+            let (lender, borrower) = (&agents[l], &agents[b]); 
+            if outcome != Outcome::Rejected {
+                log.record_loan(lender.id, borrower.id, outcome == Outcome::Cooperated); 
+            }
+            if config.trust_depth > 0 {
+                trust.refresh(lender, borrower.id); 
+                trust.refresh(borrower, lender.id); 
+            }
+            requests += 1; 
+            if outcome != Outcome::Rejected {
+                loans += 1; 
+            }
+            if outcome == Outcome::Cooperated {
+                cooperations += 1; 
+            }
+            if let Some(regret) = regret.as_mut() {
+                regret.record(lender, borrower, outcome, game); 
+            }
+        }
+        if let Some(cap) = config.energy_cap {
+            cap_stats.apply(agents, cap, config.cap_mode); 
+        }
+        if config.vouching {
+            sponsorships.release(agents, round); 
+        }
+        if config.self_reports {
+            self_report::collect(agents, config.self_report_cost, &mut log); 
+        }
+        if config.gossip_rate > 0. {
+            profile::hooks(&mut profile, || gossip::exchange(agents, config.gossip_rate, &mut log, &mut rng)); 
+        }
+        if (round + 1) % config.imitation_every.max(1) == 0 {
+            profile::hooks(&mut profile, || imitation::inform(agents, &index, &history.borrow())); 
+        }
+        profile::hooks(&mut profile, || {
+            if config.discount < 1. {
+                for agent in agents.iter_mut() {
+                    agent.strategy.discount_history(config.discount); 
+                }
+            }
+            if config.forgiveness > 0. {
+                for agent in agents.iter_mut() {
+                    agent.strategy.forgive(config.forgiveness); 
+                }
+            }
+            for agent in agents.iter_mut() {
+                agent.strategy.end_round(); 
+            }
+        }); 
+        if config.fitness_discount < 1. {
+            let weight = config.fitness_discount.powi(round); 
+            for agent in agents.iter_mut() {
+                agent.discount_round(weight); 
+            }
+        }
+        // before the dead are removed, to show them with their last energy
+        if let Some(postmortem) = postmortem.as_mut() {
+            postmortem.record(round + 1, agents); 
+        }
+        let alive = |agent: &Agent| agent.energy > config.bankruptcy; 
+        if config.delta_report {
+            for agent in agents.iter().filter(|agent| !alive(agent)) {
+                deltas.died(agent); 
+            }
+        }
+        if config.autopsy.is_some() {
+            for agent in agents.iter_mut() {
+                Trajectory::observe(&mut agent.trajectory, agent.energy, round); 
+            }
+            graveyard.bury(agents, round, alive); 
+        } else {
+            agents.retain(alive); 
+        }
+        for child in reproduction::reproduce(agents, config, round, &mut next_id, &mut births) {
+            let id = agents[child].id; 
+            agents[child].strategy.attach_history(id, HistoryView::new(&history)); 
+            if let Some(topology) = topology.as_mut() {
+                topology.attach_node(id, &mut rng); 
+            }
+        }
+        if config.trust_depth > 0 {
+            trust.retain_alive(&agents.iter().map(|agent| agent.id).collect()); 
+        }
+        if let Some(regret) = regret.as_mut().filter(|_| !warming_up) {
+            if let Err(e) = regret.log_round(round, agents, game) {
+                eprintln!("error: cannot write regret log: {}", e); 
+                process::exit(1); 
+            }
+        }
+        if let Some(profile) = profile.as_mut() {
+            profile.end_round(); 
+        }
+        if let Some(monitor) = memory_monitor.as_mut().filter(|monitor| monitor.due(round) && round + 1 < config.rounds) {
+            monitor.report(round + 1, agents, &history.borrow(), &log); 
+        }
+        if config.check {
+            if let Err(e) = check::round(round, agents, next_id) {
+                check_failed(e, postmortem.as_mut()); 
+            }
+        }
+        if let Some(dump) = dump.as_mut().filter(|dump| !warming_up && dump.due(round)) {
+            if let Err(e) = dump.write(round + 1, agents) {
+                eprintln!("error: cannot write state dump: {}", e); 
+                process::exit(1); 
+            }
+        }
+        if let Some(progress) = progress.as_mut() {
+            progress.update(round + 1, agents.len()); 
+        }
+        let stop = stop_detector.observe(agents, round)
+            .or_else(|| signals::interrupted().then_some(StopReason::Interrupted))
+            .or_else(|| {
+                let budget = config.memory_budget?; 
+                memory::resident_mb().filter(|used| *used > budget).map(|used| StopReason::MemoryBudget { used })
+            }); 
+        if let Some(r) = stop {
+            if !config.quiet && !warming_up && !reporter.final_only() {
+                report_or_exit(report(&mut reporter, agents, config, &mut deltas)); 
+            }
+            reason = r; 
+            rounds = round + 1; 
+            break; 
+        }
+    }
+    if let Some(postmortem) = postmortem.as_mut() {
+        match reason {
+            StopReason::Extinction => postmortem.dump("extinction"), 
+            StopReason::Interrupted => postmortem.dump("interrupted"), 
+            StopReason::MemoryBudget { .. } => postmortem.dump(&reason.to_string()), 
+            _ => {}
+        }
+    }
+    if !config.quiet && reporter.final_only() {
+        report_or_exit(round_report(&mut reporter, rounds, agents, config, &births, &mut deltas)); 
+    }
+    report_or_exit(reporter.flush()); 
+    drop(progress); 
+    if let Some(topology) = topology.as_ref().filter(|_| !config.quiet) {
+        topology::report(topology, agents); 
+    }
+    if let Some(autopsy) = config.autopsy.filter(|_| !config.quiet) {
+        graveyard.report(agents, autopsy, &HistoryView::new(&history), &mut rng); 
+    }
+    if let Some(winner) = config.winner.filter(|_| !config.quiet) {
+        scoring::report(agents, config, winner); 
+    }
+    if !config.quiet && config.whitewashing {
+        whitewashing.report(agents, rounds); 
+    }
+    if !config.quiet && config.escrow {
+        escrow_stats.report(); 
+    }
+    if let Some(cap) = config.energy_cap.filter(|_| !config.quiet) {
+        cap_stats.report(cap, config.cap_mode); 
+    }
+    if let Some(limit) = config.borrow_limit.filter(|_| !config.quiet) {
+        println!("borrow limit ({} per round): {} requests turned away", limit, borrow_limited); 
+    }
+    if let Some(table) = cross_table.as_ref() {
+        table.write(config); 
+    }
+    if let Some(budget) = budget.as_ref().filter(|_| !config.quiet) {
+        budget.report(); 
+    }
+    if !config.quiet && !log.is_empty() {
+        collusion::report(&log, agents); 
+    }
+    if let Some(profile) = profile.as_ref() {
+        profile.report(); 
+    }
+    if let Some(monitor) = memory_monitor.as_mut() {
+        monitor.report(rounds, agents, &history.borrow(), &log); 
+    }
+    RunSummary { rounds, reason, requests, loans, cooperations }
+}
+
+// mutable references to two distinct agents
+fn pair_mut(agents: &mut [Agent], a: usize, b: usize) -> (&mut Agent, &mut Agent) {
+    if a < b {
+        let (left, right) = agents.split_at_mut(b); 
+        (&mut left[a], &mut right[0])
+    } else {
+        let (left, right) = agents.split_at_mut(a); 
+        (&mut right[0], &mut left[b])
+    }
+}
+
+// ends the run at a failed --check, after the post-mortem of the rounds before it
+fn check_failed(message: String, postmortem: Option<&mut PostMortem>) -> ! {
+    if let Some(postmortem) = postmortem {
+        postmortem.dump(&message); 
+    }
+    eprintln!("error: {}", message); 
+    process::exit(1); 
+}
+
+// summarizes an axelrod interactions file like a run's cross-table, and writes it 
+// to the configured files for a side by side comparison
+fn import_axelrod(config: &Config) {
+    let path = config.axelrod_in.as_ref().unwrap_or_else(|| {
+        eprintln!("error: the axelrod import needs an --axelrod-in file"); 
+        process::exit(2); 
+    }); 
+    let table = CrossTable::import(path).unwrap_or_else(|e| {
+        eprintln!("error: {}", e); 
+        process::exit(1); 
+    }); 
+    println!("Players of {}:", path); 
+    table.print(); 
+    table.write(config); 
+}
+
+// dumps a status snapshot to stderr and waits for the resume signal
+fn pause(round: i32, agents: &[Agent], config: &Config) {
+    let stderr = &mut io::stderr(); 
+    eprintln!("paused at the start of round {}, send SIGUSR2 to resume (pid {})", round, process::id()); 
+    report_or_exit(report(stderr, agents, config, &mut Deltas::default())); 
+    signals::wait_for_resume(); 
+    eprintln!("resumed"); 
+}
+
+fn report_or_exit(result: io::Result<()>) {
+    if let Err(e) = result {
+        eprintln!("error: cannot write report: {}", e); 
+        process::exit(1); 
+    }
+}
+
+// the report on the state at the start of `round`
+fn round_report(
+    out: &mut dyn Write, 
+    round: i32, 
+    agents: &[Agent], 
+    config: &Config, 
+    births: &Births, 
+    deltas: &mut Deltas
+) -> io::Result<()> {
+    writeln!(out, "Round {}.", round)?; 
+    report(out, agents, config, deltas)?; 
+    if config.reproduction_energy.is_some() {
+        writeln!(
+            out, 
+            "births: {} offspring born, {} lost the competition for slots (population {}{})", 
+            births.born, 
+            births.culled, 
+            agents.len(), 
+            config.population_cap.map_or(String::new(), |cap| format!(" of {}", cap))
+        )?; 
+    }
+    Ok(())
+}
+
+// with a delta report, the count and mean energy of every type also show their 
+// change since the previous report, and the deaths in between
+fn report(out: &mut dyn Write, agents: &[Agent], config: &Config, deltas: &mut Deltas) -> io::Result<()> {
+    // per-agent counts of encounters are scaled up to estimate those of a full 
+    // schedule when only a fraction of the pairs is played
+    let scale = 1. / config.pair_fraction; 
+    // println!("simulating agents: {:?}", agents); 
     let mut count: HashMap<String, i32> = HashMap::new(); 
     let mut sum: HashMap<String, f64> = HashMap::new(); 
 
     for agent in agents.iter() {
-        let st = agent.strategy.get_type();
+        let st = agent.kind();
         match count.get_mut(&st.to_string()) {
             Some(n) => { *n += 1; }, 
             None => { count.insert(st.to_string(), 1); }
@@ -277,36 +2924,168 @@ fn report(agents: &Vec<Agent>) {
         };
     }
 
+    let accuracy = metrics::reputation_accuracy(agents); 
+    let exploitation = metrics::exploitation(agents); 
+    let damage = metrics::slander_damage(agents); 
+    let self_reported = metrics::self_reported(agents); 
+    let karma = metrics::karma(agents); 
+    let devices = metrics::devices(agents); 
+    let vetoes = metrics::risk_vetoes(agents); 
+    let broken = metrics::promises_broken(agents); 
+    let debt = metrics::debt(agents); 
+    let optimism = metrics::optimism(agents); 
+
     let mut keys = count.keys().collect::<Vec<&String>>(); 
     keys.sort(); 
 
     for strategy in keys.iter() {
         let c = count.get(*strategy).unwrap();
-        println!("{}:", strategy); 
-        println!(" - count: {}", c); 
-        match sum.get(*strategy) {
-            Some(s) => {
-                println!(" - mean energy: {:.2}", s / (*c as f64))
-            }, 
+        writeln!(out, "{}:", strategy)?; 
+        let mean = sum[*strategy] / (*c as f64); 
+        match deltas.change(strategy, *c, mean).filter(|_| config.delta_report) {
+            Some((dc, de)) => {
+                writeln!(out, " - count: {} ({:+})", c, dc)?; 
+                writeln!(out, " - mean energy: {:.2} ({:+.2})", mean, de)?; 
+            }
+            None => {
+                writeln!(out, " - count: {}", c)?; 
+                writeln!(out, " - mean energy: {:.2}", mean)?
+            }
+        }
+        if config.delta_report {
+            writeln!(out, " - deaths since the previous report: {}", deltas.deaths(strategy))?; 
+        }
+        if let Some((n, total)) = debt.get(*strategy) {
+            writeln!(out, " - in debt: {} agents, mean debt {:.2}", n, total / (*n as f64))?; 
+        }
+        match accuracy.get(*strategy) {
+            Some((Some(r), n)) => writeln!(out, " - reputation accuracy: {:.3} ({} scores)", r, n)?, 
+            Some((None, n)) => writeln!(out, " - reputation accuracy: n/a ({} scores)", n)?, 
             None => {}
-        };
+        }
+        if let Some((rejections, slander)) = damage.get(*strategy) {
+            writeln!(
+                out, 
+                " - rejected {:.1} times per agent, {:.1} false negative reports per agent", 
+                *rejections as f64 * scale / (*c as f64), 
+                *slander as f64 * scale / (*c as f64)
+            )?; 
+        }
+        if let Some(n) = self_reported.get(*strategy) {
+            writeln!(out, " - self-reported loans: {:.1} per agent", *n as f64 * scale / (*c as f64))?; 
+        }
+        if let Some((n, costs)) = broken.get(*strategy) {
+            writeln!(
+                out, 
+                " - broke {:.1} promises per agent, spent {:.1} energy per agent on promises", 
+                *n as f64 * scale / (*c as f64), 
+                costs * scale / (*c as f64)
+            )?; 
+        }
+        if let Some((optimists, exploited)) = optimism.get(*strategy) {
+            writeln!(
+                out, 
+                " - still optimistic: {} of {} agents, exploited by strangers {:.1} times per agent", 
+                optimists, c, *exploited as f64 / (*c as f64)
+            )?; 
+        }
+        if let Some(n) = vetoes.get(*strategy) {
+            writeln!(out, " - declined {:.1} requests per agent out of risk aversion", *n as f64 * scale / (*c as f64))?; 
+        }
+        if let Some((quality, replacements)) = devices.get(*strategy) {
+            writeln!(
+                out, 
+                " - devices: mean quality {:.3}, {:.1} replacements per agent", 
+                quality / (*c as f64), 
+                *replacements as f64 / (*c as f64)
+            )?; 
+        }
+        if let Some(k) = karma.get(*strategy) {
+            writeln!(
+                out, 
+                " - karma: {:.1} tokens held, {:.1} minted, {:.1} spent per agent", 
+                k.held as f64 / (*c as f64), 
+                k.minted as f64 / (*c as f64), 
+                k.spent as f64 / (*c as f64)
+            )?; 
+        }
+        if let Some(e) = exploitation.get(*strategy) {
+            writeln!(
+                out, 
+                " - exploitation index: {:.3} (per living agent: lost {:.1} to defections, gained {:.1} from cooperation, {:.1} exchanges)", 
+                e.index(), 
+                e.lost * scale / (*c as f64), 
+                e.gained * scale / (*c as f64), 
+                e.exchanges as f64 * scale / (*c as f64)
+            )?; 
+        }
+    }
+
+    if config.delta_report {
+        for (strategy, c) in deltas.extinct(&count) {
+            writeln!(out, "{}:", strategy)?; 
+            writeln!(out, " - count: 0 ({:+})", -c)?; 
+            writeln!(out, " - deaths since the previous report: {}", deltas.deaths(&strategy))?; 
+        }
+        deltas.advance(&count, &sum); 
     }
 
-    println!()
+    if !config.network_stats {
+        return writeln!(out); 
+    }
+    let network = network::trust_network(agents); 
+    writeln!(
+        out, 
+        "trust network: {} positive edges, reciprocity {:.3}, clustering {:.3}, {} components, {} isolated", 
+        network.edges, 
+        network.reciprocity, 
+        network.clustering, 
+        network.components, 
+        network.isolated
+    )?; 
+    writeln!(
+        out, 
+        " - in-degree min/median/max: {}/{}/{}, out-degree min/median/max: {}/{}/{}", 
+        network.in_degree.0, 
+        network.in_degree.1, 
+        network.in_degree.2, 
+        network.out_degree.0, 
+        network.out_degree.1, 
+        network.out_degree.2
+    )?; 
+
+    writeln!(out)
 }
 
-fn encounter(lender: &mut Agent, borrower: &mut Agent) {
-    if lender.strategy.accept_or_reject_request(borrower.id) == ACCEPT {
-        let coop = borrower.strategy.coop_or_defect(lender.id);
-        lender.strategy.notify_coop_or_defect(borrower.id, coop); 
-        if coop == COOP {
-            lender.energy += GP.lender_coop_payout; 
-            borrower.energy += GP.borrower_coop_payout; 
-        } else {
-            lender.energy += GP.lender_defect_payout; 
-            borrower.energy += GP.borrower_defect_payout; 
-        }
+// the lender only decides on requests it has not declined when triaging its queue
+fn encounter(lender: &mut Agent, borrower: &mut Agent, game: &GameParams, seen: bool) -> Outcome {
+    lender.requests_received += 1; 
+    if seen && lender.strategy.accept_or_reject_request(borrower.id) == ACCEPT {
+        lend(lender, borrower, game)
     } else {
         borrower.strategy.notify_about_rejection(lender.id); 
+        borrower.rejections += 1; 
+        Outcome::Rejected
+    }
+}
+
+// the borrower uses the device and decides whether to return it
+fn lend(lender: &mut Agent, borrower: &mut Agent, game: &GameParams) -> Outcome {
+    let coop = borrower.strategy.coop_or_defect(lender.id);
+    lender.strategy.notify_coop_or_defect(borrower.id, coop); 
+    if coop == COOP {
+        lender.energy += game.lender_coop_payout; 
+        borrower.energy += game.borrower_coop_payout; 
+        borrower.cooperations += 1; 
+        lender.gained_from_cooperation += game.lender_coop_payout; 
+        borrower.gained_from_cooperation += game.borrower_coop_payout; 
+    } else {
+        lender.energy += game.lender_defect_payout; 
+        borrower.energy += game.borrower_defect_payout; 
+        borrower.defections += 1; 
+        lender.lost_to_defections -= game.lender_defect_payout; 
     }
+    lender.exchanges += 1; 
+    borrower.exchanges += 1; 
+    if coop == COOP { Outcome::Cooperated } else { Outcome::Defected }
 }
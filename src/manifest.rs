@@ -0,0 +1,36 @@
+use std::env;
+use std::io::{self, Write};
+
+use crate::config::Config;
+use crate::STRATEGY_REGISTRY;
+
+// Everything needed to trace a result back to the exact run that produced it. 
+// The resolved configuration is given as command line options, so passing it 
+// back to the same commit reproduces the run. 
+pub fn lines(config: &Config) -> Vec<String> {
+    let options = config.entries()
+        .iter()
+        .map(|(key, value)| format!("--{} {}", key, value))
+        .collect::<Vec<String>>()
+        .join(" "); 
+    let strategies = STRATEGY_REGISTRY.iter()
+        .map(|(name, version)| format!("{} v{}", name, version))
+        .collect::<Vec<String>>()
+        .join(", "); 
+    vec![
+        format!("simulation {}", env!("CARGO_PKG_VERSION")), 
+        format!("git commit: {}", option_env!("GIT_COMMIT").unwrap_or("unknown")), 
+        format!("arguments: {}", env::args().skip(1).collect::<Vec<String>>().join(" ")), 
+        format!("resolved configuration: {} {}", config.command, options), 
+        format!("seed: {}", config.seed), 
+        format!("strategies: {}", strategies), 
+    ]
+}
+
+// writes the manifest as '#' comment lines, e.g. at the top of a csv file
+pub fn write<W: Write>(out: &mut W, config: &Config) -> io::Result<()> {
+    for line in lines(config) {
+        writeln!(out, "# {}", line)?; 
+    }
+    Ok(())
+}
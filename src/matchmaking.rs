@@ -0,0 +1,306 @@
+use std::collections::HashMap;
+
+use rand::distributions::WeightedIndex;
+use rand::prelude::Distribution;
+use rand::Rng;
+use rand::rngs::StdRng;
+use rand::seq::{index, SliceRandom};
+
+use crate::collusion::InteractionLog;
+use crate::config::{Config, Matchmaking};
+use crate::topology::Topology;
+use crate::Agent;
+
+// A (lender, borrower) index pair of a round's schedule, whether it was deferred 
+// from the last round and whether the lender declined it when triaging its queue. 
+#[derive(Clone, Copy)]
+pub struct Request {
+    pub lender: usize, 
+    pub borrower: usize, 
+    pub deferred: bool, 
+    pub declined: bool, 
+}
+
+impl Request {
+    pub fn new(lender: usize, borrower: usize) -> Self {
+        Request { lender, borrower, deferred: false, declined: false }
+    }
+}
+
+// The (lender, borrower) index pairs of one round, in order. 
+// 
+// All pairs: every agent asks every other agent once, both requests of a pair 
+// back to back. With symmetric roles, every pair plays a single encounter with 
+// lender and borrower drawn at random. 
+// 
+// Weighted: every agent makes `requests` requests, each to a lender sampled with 
+// weight (1 + loans the lender granted it so far) * (1 + the lender's own share of 
+// returned devices), so requests are routed to lenders that accepted the borrower 
+// before and to known cooperators, like on a sharing platform. 
+// 
+// With a pair fraction below 1, only that share of the pairs is scheduled, drawn 
+// anew every round; without a topology they are sampled directly, without a pass 
+// over all n² pairs. 
+// 
+// On a topology, only neighbors are paired. The schedule is shuffled every round 
+// unless the legacy fixed order (by agent index) is configured. 
+pub fn pairs(
+    agents: &[Agent], 
+    config: &Config, 
+    topology: Option<&Topology>, 
+    index: &HashMap<usize, usize>, 
+    log: &InteractionLog, 
+    rng: &mut StdRng
+) -> Vec<Request> {
+    let n = agents.len(); 
+    match config.matchmaking {
+        Matchmaking::AllPairs => {
+            let fraction = config.pair_fraction; 
+            let mut pairs = match topology {
+                Some(topology) => {
+                    let mut pairs = topology.pairs(agents, index); 
+                    if fraction < 1. {
+                        pairs.retain(|_| rng.gen_bool(fraction)); 
+                    }
+                    pairs
+                }
+                None if fraction < 1. => sample_pairs(n, fraction, rng), 
+                None => {
+                    let mut pairs = Vec::with_capacity(n * n.saturating_sub(1) / 2); 
+                    for alice in 0..n {
+                        for bob in alice + 1..n {
+                            pairs.push((alice, bob)); 
+                        }
+                    }
+                    pairs
+                }
+            };
+            if !config.fixed_order {
+                pairs.shuffle(rng); 
+            }
+            if config.symmetric_roles {
+                return pairs.into_iter()
+                    .map(|(alice, bob)| if rng.gen_bool(0.5) { Request::new(alice, bob) } else { Request::new(bob, alice) })
+                    .collect(); 
+            }
+            pairs.into_iter()
+                .flat_map(|(alice, bob)| [Request::new(alice, bob), Request::new(bob, alice)])
+                .collect()
+        }
+        Matchmaking::Weighted => {
+            let mut pairs = vec![]; 
+            if n < 2 {
+                return pairs; 
+            }
+            for b in 0..n {
+                let weights: Vec<f64> = agents.iter()
+                    .enumerate()
+                    .map(|(l, lender)| {
+                        if l == b || topology.is_some_and(|t| !t.are_neighbors(lender.id, agents[b].id)) {
+                            0.
+                        } else {
+                            let accepted = log.loans_granted(lender.id, agents[b].id) as f64; 
+                            (1. + accepted) * (1. + lender.cooperation_rate().unwrap_or(0.))
+                        }
+                    })
+                    .collect(); 
+                // no living neighbors
+                let lenders = match WeightedIndex::new(&weights) {
+                    Ok(lenders) => lenders, 
+                    Err(_) => continue
+                };
+                for _ in 0..config.requests {
+                    pairs.push(Request::new(lenders.sample(rng), b)); 
+                }
+            }
+            if !config.fixed_order {
+                pairs.shuffle(rng); 
+            }
+            pairs
+        }
+    }
+}
+
+// `fraction` of the n (n - 1) / 2 pairs (lower index first) sampled without 
+// replacement, sorted by index
+fn sample_pairs(n: usize, fraction: f64, rng: &mut StdRng) -> Vec<(usize, usize)> {
+    let total = n * n.saturating_sub(1) / 2; 
+    let amount = (total as f64 * fraction).round() as usize; 
+    let mut pairs: Vec<(usize, usize)> = index::sample(rng, total, amount.min(total))
+        .into_iter()
+        .map(unrank)
+        .collect(); 
+    pairs.sort_unstable(); 
+    pairs
+}
+
+// the k-th pair (alice, bob) with alice < bob, ordered by bob first
+fn unrank(k: usize) -> (usize, usize) {
+    let mut bob = ((1. + (1. + 8. * k as f64).sqrt()) / 2.) as usize; 
+    while bob * (bob - 1) / 2 > k {
+        bob -= 1; 
+    }
+    while (bob + 1) * bob / 2 <= k {
+        bob += 1; 
+    }
+    (k - bob * (bob - 1) / 2, bob)
+}
+
+// Two-phase resolution: the requests of a round grouped into per-lender queues, 
+// lenders in the order of their first request. Every lender is shown the borrower 
+// ids of its queue in arrival order and answers with the borrowers it handles, in 
+// the order it handles them, each entry standing for one of that borrower's 
+// requests; the remaining requests follow, declined. 
+pub fn resolve_in_two_phases(agents: &mut [Agent], schedule: Vec<Request>) -> Vec<Request> {
+    let mut lenders = vec![]; 
+    let mut queues: HashMap<usize, Vec<Request>> = HashMap::new(); 
+    for request in schedule {
+        queues.entry(request.lender)
+            .or_insert_with(|| { lenders.push(request.lender); vec![] })
+            .push(request); 
+    }
+    let mut resolved = Vec::with_capacity(queues.values().map(Vec::len).sum()); 
+    for l in lenders {
+        let mut queue: Vec<Option<Request>> = queues.remove(&l).unwrap_or_default().into_iter().map(Some).collect(); 
+        let borrowers: Vec<usize> = queue.iter().flatten().map(|request| agents[request.borrower].id).collect(); 
+        for id in agents[l].strategy.respond_to_requests(&borrowers) {
+            let pending = queue.iter_mut()
+                .find(|request| request.is_some_and(|request| agents[request.borrower].id == id)); 
+            if let Some(request) = pending.and_then(Option::take) {
+                resolved.push(request); 
+            }
+        }
+        resolved.extend(queue.into_iter().flatten().map(|request| Request { declined: true, ..request })); 
+    }
+    resolved
+}
+
+#[cfg(test)]
+mod tests {
+    use core::fmt; 
+    use std::collections::HashSet; 
+
+    use rand::SeedableRng; 
+
+    use super::*; 
+    use crate::{BorrowerAction, LenderAction, Strategy, ACCEPT, COOP}; 
+
+    // lender that handles the borrowers of its answer, in that order
+    #[derive(Clone)]
+    struct Triage {
+        answer: Vec<usize>, 
+    }
+
+    impl fmt::Display for Triage {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "triage")
+        }
+    }
+
+    impl Strategy for Triage {
+        fn accept_or_reject_request(&mut self, _borrower: usize) -> BorrowerAction {
+            ACCEPT
+        }
+        fn notify_about_rejection(&mut self, _lender: usize) {
+        }
+        fn coop_or_defect(&mut self, _lender: usize) -> LenderAction {
+            COOP
+        }
+        fn notify_coop_or_defect(&mut self, _borrower: usize, _coop: BorrowerAction) {
+        }
+        fn get_type(&self) -> String {
+            "triage".into()
+        }
+        fn respond_to_requests(&mut self, _queue: &[usize]) -> Vec<usize> {
+            self.answer.clone()
+        }
+        fn clone(&self) -> Box<dyn Strategy> {
+            Box::new(Clone::clone(self))
+        }
+    }
+
+    // agents with ids 10, 11, ... answering with the given borrower ids
+    fn agents(answers: &[&[usize]]) -> Vec<Agent> {
+        answers.iter()
+            .enumerate()
+            .map(|(i, answer)| Agent::new(10 + i, Box::new(Triage { answer: answer.to_vec() })))
+            .collect()
+    }
+
+    fn resolved(agents: &mut [Agent], schedule: &[(usize, usize)]) -> Vec<(usize, usize, bool)> {
+        let schedule = schedule.iter().map(|(l, b)| Request::new(*l, *b)).collect(); 
+        resolve_in_two_phases(agents, schedule).into_iter()
+            .map(|request| (request.lender, request.borrower, request.declined))
+            .collect()
+    }
+
+    #[test]
+    fn lenders_handle_their_queues_in_the_order_they_answer() {
+        let mut agents = agents(&[&[12, 13], &[], &[11], &[]]); 
+        let schedule = [(0, 1), (2, 1), (0, 2), (0, 3), (0, 2)]; 
+        assert_eq!(resolved(&mut agents, &schedule), vec![
+            (0, 2, false), 
+            (0, 3, false), 
+            (0, 1, true), 
+            (0, 2, true), 
+            (2, 1, false), 
+        ]); 
+    }
+
+    #[test]
+    fn answers_beyond_the_queue_are_ignored() {
+        let mut agents = agents(&[&[11, 11, 13, 10], &[], &[]]); 
+        assert_eq!(resolved(&mut agents, &[(0, 1), (0, 2)]), vec![(0, 1, false), (0, 2, true)]); 
+    }
+
+    #[test]
+    fn an_empty_schedule_resolves_to_nothing() {
+        let mut agents = agents(&[&[]]); 
+        assert!(resolved(&mut agents, &[]).is_empty()); 
+    }
+
+    #[test]
+    fn unrank_enumerates_every_pair_once_ordered_by_bob() {
+        let n = 50; 
+        let pairs: Vec<(usize, usize)> = (0..n * (n - 1) / 2).map(unrank).collect(); 
+        let mut expected = vec![]; 
+        for bob in 1..n {
+            for alice in 0..bob {
+                expected.push((alice, bob)); 
+            }
+        }
+        assert_eq!(pairs, expected); 
+    }
+
+    #[test]
+    fn unrank_is_exact_for_large_ranks() {
+        let bob = 3_000_000; 
+        let first = bob * (bob - 1) / 2; 
+        assert_eq!(unrank(first - 1), (bob - 2, bob - 1)); 
+        assert_eq!(unrank(first), (0, bob)); 
+        assert_eq!(unrank(first + bob - 1), (bob - 1, bob)); 
+    }
+
+    #[test]
+    fn sample_pairs_draws_the_share_of_distinct_pairs() {
+        let mut rng = StdRng::seed_from_u64(1); 
+        let pairs = sample_pairs(100, 0.1, &mut rng); 
+        assert_eq!(pairs.len(), 495); 
+        assert!(pairs.windows(2).all(|w| w[0] < w[1])); 
+        assert!(pairs.iter().all(|(alice, bob)| alice < bob && *bob < 100)); 
+    }
+
+    #[test]
+    fn sample_pairs_with_fraction_one_draws_all_pairs() {
+        let mut rng = StdRng::seed_from_u64(1); 
+        let pairs: HashSet<(usize, usize)> = sample_pairs(20, 1., &mut rng).into_iter().collect(); 
+        assert_eq!(pairs.len(), 190); 
+    }
+
+    #[test]
+    fn sample_pairs_of_fewer_than_two_agents_is_empty() {
+        let mut rng = StdRng::seed_from_u64(1); 
+        assert!(sample_pairs(0, 0.5, &mut rng).is_empty()); 
+        assert!(sample_pairs(1, 0.5, &mut rng).is_empty()); 
+    }
+}
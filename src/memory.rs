@@ -0,0 +1,110 @@
+use std::fs;
+use std::mem;
+
+use crate::collusion::InteractionLog;
+use crate::config::Config;
+use crate::history::PairHistory;
+use crate::Agent;
+
+// Resident memory of this process in MiB, read from /proc on Linux; None where 
+// it isn't available. 
+pub fn resident_mb() -> Option<u64> {
+    let status = fs::read_to_string("/proc/self/status").ok()?; 
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?; 
+    let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?; 
+    Some(kb / 1024)
+}
+
+// Approximate heap memory of a hash map with `len` entries: hashbrown keeps 
+// buckets at most 7/8 full with one control byte per bucket. 
+pub fn map_bytes<K, V>(len: usize) -> usize {
+    len * 8 / 7 * (mem::size_of::<(K, V)>() + 1)
+}
+
+fn mib(bytes: usize) -> f64 {
+    bytes as f64 / (1024. * 1024.)
+}
+
+// The state of a run that grows with its length and the population, by component, 
+// in bytes. Strategies' state other than their reputation tables isn't counted. 
+struct Usage {
+    agents: usize, 
+    tables: usize, 
+    history: usize, 
+    log: usize, 
+}
+
+impl Usage {
+    fn measure(agents: &[Agent], history: &PairHistory, log: &InteractionLog) -> Usage {
+        let entries: usize = agents.iter()
+            .filter_map(|agent| agent.strategy.reputations())
+            .map(|reputations| reputations.len())
+            .sum(); 
+        Usage {
+            agents: mem::size_of_val(agents), 
+            tables: map_bytes::<usize, f64>(entries), 
+            history: history.memory(), 
+            log: log.memory(), 
+        }
+    }
+
+    fn total(&self) -> usize {
+        self.agents + self.tables + self.history + self.log
+    }
+}
+
+// Reports the approximate memory use every few rounds and at the end of a run, 
+// and warns once when the growth since the previous report, extrapolated to the 
+// remaining rounds, would exceed the memory budget. 
+pub struct MemoryMonitor {
+    every: i32, 
+    rounds: i32, 
+    budget: Option<u64>, 
+    // round and estimated MiB in use at the previous report
+    previous: Option<(i32, f64)>, 
+    warned: bool, 
+}
+
+impl MemoryMonitor {
+    pub fn new(config: &Config) -> Option<MemoryMonitor> {
+        let every = config.memory_every?; 
+        Some(MemoryMonitor {
+            every: every.max(1), 
+            rounds: config.rounds, 
+            budget: config.memory_budget, 
+            previous: None, 
+            warned: false, 
+        })
+    }
+
+    pub fn due(&self, round: i32) -> bool {
+        (round + 1) % self.every == 0
+    }
+
+    // `round` is the number of completed rounds
+    pub fn report(&mut self, round: i32, agents: &[Agent], history: &PairHistory, log: &InteractionLog) {
+        let usage = Usage::measure(agents, history, log); 
+        let resident = resident_mb(); 
+        println!(
+            "memory after round {}: agents {:.1} MiB, reputation tables {:.1} MiB, pair history {:.1} MiB, interaction log {:.1} MiB, total {:.1} MiB{}", 
+            round, mib(usage.agents), mib(usage.tables), mib(usage.history), mib(usage.log), mib(usage.total()), 
+            resident.map_or(String::new(), |mb| format!(" (resident {} MiB)", mb)), 
+        ); 
+        let estimated = mib(usage.total()); 
+        if let (Some(budget), Some((before, then))) = (self.budget, self.previous) {
+            // growth of the estimate, which is finer than the resident memory, from 
+            // the resident memory if known, as it includes what isn't counted
+            let growth = (estimated - then) / (round - before).max(1) as f64; 
+            let used = resident.map_or(estimated, |mb| mb as f64); 
+            let projected = used + growth * (self.rounds - round) as f64; 
+            if !self.warned && projected > budget as f64 {
+                eprintln!(
+                    "warning: memory use grows by {:.2} MiB per round and would reach {:.0} MiB by round {}, over the budget of {} MiB", 
+                    growth, projected, self.rounds, budget, 
+                ); 
+                self.warned = true; 
+            }
+        }
+        self.previous = Some((round, estimated)); 
+    }
+}
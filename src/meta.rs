@@ -0,0 +1,97 @@
+use std::collections::BTreeMap;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::config::{Config, Range};
+use crate::{gen_agents, population, manifest, seed, simulate, GameParams};
+
+fn sample<R: Rng>(range: &Range, rng: &mut R) -> f64 {
+    if range.min == range.max {
+        range.min
+    } else {
+        rng.gen_range(range.min..=range.max)
+    }
+}
+
+// Runs the configured population once for each of `payoff_samples` payoff matrices 
+// drawn uniformly from the configured ranges. In every run the strategy types are 
+// ranked by their mean final energy per initial agent (dead agents count as 0); 
+// the types are then ordered by average rank, with the worst rank as a robustness 
+// measure. 
+pub fn run(config: &Config) {
+    for line in manifest::lines(config) {
+        println!("# {}", line); 
+    }
+    // stream 0 samples the payoffs, sample i runs with stream i + 1
+    let mut rng = StdRng::seed_from_u64(seed::derive(config.seed, 0)); 
+    let run_config = Config {
+        quiet: true, 
+        regret_out: None, 
+        dump_out: None, 
+        snapshots: 0, 
+        axelrod_summary: None, 
+        axelrod_matrix: None, 
+        ..config.clone()
+    };
+
+    // strategy type -> ranks over all samples
+    let mut ranks: BTreeMap<String, Vec<usize>> = BTreeMap::new(); 
+    for i in 0..config.payoff_samples {
+        let game = GameParams {
+            borrower_defect_payout: sample(&config.borrower_defect_range, &mut rng), 
+            borrower_coop_payout: sample(&config.borrower_coop_range, &mut rng), 
+            lender_defect_payout: sample(&config.lender_defect_range, &mut rng), 
+            lender_coop_payout: sample(&config.lender_coop_range, &mut rng), 
+        };
+        let run_seed = seed::derive(config.seed, i as u64 + 1); 
+        let mut agents = gen_agents(population(config), &game, run_seed, config.jitter()); 
+        let mut initial: BTreeMap<String, usize> = BTreeMap::new(); 
+        for agent in agents.iter() {
+            *initial.entry(agent.kind()).or_insert(0) += 1; 
+        }
+        let summary = simulate(&mut agents, &Config { game, seed: run_seed, ..run_config.clone() }); 
+
+        let mut energy: BTreeMap<String, f64> = initial.keys().map(|st| (st.clone(), 0.)).collect(); 
+        for agent in agents.iter() {
+            *energy.get_mut(&agent.kind()).unwrap() += agent.fitness(config); 
+        }
+        let scores: Vec<(String, f64)> = energy.into_iter()
+            .map(|(st, e)| {
+                let score = e / initial[&st] as f64; 
+                (st, score)
+            })
+            .collect(); 
+
+        println!(
+            "Sample {}: payoffs borrower {:.2}/{:.2}, lender {:.2}/{:.2} (defect/coop), {} rounds", 
+            i, 
+            game.borrower_defect_payout, 
+            game.borrower_coop_payout, 
+            game.lender_defect_payout, 
+            game.lender_coop_payout, 
+            summary.rounds
+        ); 
+        for (st, score) in scores.iter() {
+            // competition ranking, ties share the better rank
+            let rank = 1 + scores.iter().filter(|(_, other)| other > score).count(); 
+            println!(" - {}: rank {}, mean energy {:.2}", st, rank, score); 
+            ranks.entry(st.clone()).or_default().push(rank); 
+        }
+    }
+
+    let mut robustness: Vec<(String, f64, usize)> = ranks.into_iter()
+        .map(|(st, r)| {
+            let average = r.iter().sum::<usize>() as f64 / r.len() as f64; 
+            let worst = *r.iter().max().unwrap(); 
+            (st, average, worst)
+        })
+        .collect(); 
+    robustness.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap().then(a.2.cmp(&b.2))); 
+
+    println!(); 
+    println!("Robustness over {} payoff matrices:", config.payoff_samples); 
+    for (st, average, worst) in robustness.iter() {
+        println!(" - {}: average rank {:.2}, worst rank {}", st, average, worst); 
+    }
+}
@@ -0,0 +1,252 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::Agent;
+
+// Pearson correlation between the reputation scores held by each strategy type and 
+// the observed cooperation rate of the scored partners. Only partners that are 
+// still alive and have borrowed at least once are taken into account. 
+// Returns strategy type -> (correlation, number of scores); the correlation is 
+// undefined if there are fewer than two scores or either side has no variance. 
+pub fn reputation_accuracy(agents: &[Agent]) -> BTreeMap<String, (Option<f64>, usize)> {
+    let coop_rates: HashMap<usize, f64> = agents.iter()
+        .filter_map(|agent| agent.cooperation_rate().map(|rate| (agent.id, rate)))
+        .collect(); 
+
+    let mut samples: BTreeMap<String, Correlation> = BTreeMap::new(); 
+    for agent in agents.iter() {
+        if let Some(reputations) = agent.strategy.reputations() {
+            let correlation = samples.entry(agent.kind()).or_default(); 
+            for (partner, reputation) in reputations.iter() {
+                if let Some(rate) = coop_rates.get(partner) {
+                    correlation.add(*reputation, *rate); 
+                }
+            }
+        }
+    }
+
+    samples.into_iter()
+        .map(|(strategy, correlation)| (strategy, (correlation.value(), correlation.n)))
+        .collect()
+}
+
+// Pearson correlation accumulated one sample at a time (Welford), so that no 
+// sample list proportional to the number of reputation scores is kept. 
+#[derive(Default)]
+struct Correlation {
+    n: usize, 
+    mean_x: f64, 
+    mean_y: f64, 
+    cov: f64, 
+    var_x: f64, 
+    var_y: f64, 
+}
+
+impl Correlation {
+    fn add(&mut self, x: f64, y: f64) {
+        self.n += 1; 
+        let dx = x - self.mean_x; 
+        let dy = y - self.mean_y; 
+        self.mean_x += dx / self.n as f64; 
+        self.mean_y += dy / self.n as f64; 
+        self.cov += dx * (y - self.mean_y); 
+        self.var_x += dx * (x - self.mean_x); 
+        self.var_y += dy * (y - self.mean_y); 
+    }
+
+    fn value(&self) -> Option<f64> {
+        if self.n < 2 || self.var_x <= 0. || self.var_y <= 0. {
+            None
+        } else {
+            Some(self.cov / (self.var_x * self.var_y).sqrt())
+        }
+    }
+}
+
+pub struct Exploitation {
+    pub lost: f64, 
+    pub gained: f64, 
+    pub exchanges: u32, 
+}
+
+impl Exploitation {
+    // share of the energy moved through loans that was lost to defections: 0 means 
+    // never exploited, 1 means nothing but losses. Strategies with a low index and 
+    // few exchanges survive by avoiding interaction rather than by choosing partners.
+    pub fn index(&self) -> f64 {
+        let total = self.lost + self.gained.max(0.); 
+        if total == 0. {
+            0.
+        } else {
+            self.lost / total
+        }
+    }
+}
+
+// Cumulative exploitation of the living agents, per strategy type. The losses of 
+// agents that died are gone with them, so a type whose exploited agents die off 
+// looks less exploited than it was. 
+pub fn exploitation(agents: &[Agent]) -> BTreeMap<String, Exploitation> {
+    let mut result: BTreeMap<String, Exploitation> = BTreeMap::new(); 
+    for agent in agents.iter() {
+        let entry = result.entry(agent.kind()).or_insert(Exploitation {
+            lost: 0., 
+            gained: 0., 
+            exchanges: 0, 
+        }); 
+        entry.lost += agent.lost_to_defections; 
+        entry.gained += agent.gained_from_cooperation; 
+        entry.exchanges += agent.exchanges; 
+    }
+    result
+}
+
+// (rejections, false negative reports) received by the living agents, per 
+// strategy type; compares how much honest agents suffer from slander
+pub fn slander_damage(agents: &[Agent]) -> BTreeMap<String, (u32, u32)> {
+    let mut result: BTreeMap<String, (u32, u32)> = BTreeMap::new(); 
+    for agent in agents.iter() {
+        let entry = result.entry(agent.kind()).or_insert((0, 0)); 
+        entry.0 += agent.rejections; 
+        entry.1 += agent.false_negative_reports; 
+    }
+    result
+}
+
+// fake loans the living agents took part in, per strategy type; types without 
+// any are left out
+pub fn self_reported(agents: &[Agent]) -> BTreeMap<String, u32> {
+    let mut result: BTreeMap<String, u32> = BTreeMap::new(); 
+    for agent in agents.iter().filter(|agent| agent.self_reported > 0) {
+        *result.entry(agent.kind()).or_insert(0) += agent.self_reported; 
+    }
+    result
+}
+
+// devices stolen after a promise and energy spent on promises per strategy type; 
+// types that neither broke nor paid for a promise are left out
+pub fn promises_broken(agents: &[Agent]) -> BTreeMap<String, (u32, f64)> {
+    let mut result: BTreeMap<String, (u32, f64)> = BTreeMap::new(); 
+    for agent in agents.iter().filter(|agent| agent.promises_broken > 0 || agent.signal_costs > 0.) {
+        let entry = result.entry(agent.kind()).or_insert((0, 0.)); 
+        entry.0 += agent.promises_broken; 
+        entry.1 += agent.signal_costs; 
+    }
+    result
+}
+
+// agents in debt and their total debt per strategy type; types without debtors 
+// are left out
+pub fn debt(agents: &[Agent]) -> BTreeMap<String, (u32, f64)> {
+    let mut result: BTreeMap<String, (u32, f64)> = BTreeMap::new(); 
+    for agent in agents.iter().filter(|agent| agent.energy < 0.) {
+        let entry = result.entry(agent.kind()).or_insert((0, 0.)); 
+        entry.0 += 1; 
+        entry.1 -= agent.energy; 
+    }
+    result
+}
+
+// requests declined out of risk aversion per strategy type; types without any 
+// are left out
+pub fn risk_vetoes(agents: &[Agent]) -> BTreeMap<String, u32> {
+    let mut result: BTreeMap<String, u32> = BTreeMap::new(); 
+    for agent in agents.iter().filter(|agent| agent.strategy.risk_vetoes() > 0) {
+        *result.entry(agent.kind()).or_insert(0) += agent.strategy.risk_vetoes(); 
+    }
+    result
+}
+
+// agents still lending to strangers and summed defections of strangers per 
+// strategy type, only for types whose optimism decays
+pub fn optimism(agents: &[Agent]) -> BTreeMap<String, (u32, u32)> {
+    let mut result: BTreeMap<String, (u32, u32)> = BTreeMap::new(); 
+    for agent in agents.iter() {
+        if let Some((optimistic, exploited)) = agent.strategy.optimism() {
+            let entry = result.entry(agent.kind()).or_insert((0, 0)); 
+            entry.0 += optimistic as u32; 
+            entry.1 += exploited; 
+        }
+    }
+    result
+}
+
+// summed device quality and replacements per strategy type, only for types whose 
+// devices have seen any wear
+pub fn devices(agents: &[Agent]) -> BTreeMap<String, (f64, u32)> {
+    let mut result: BTreeMap<String, (f64, u32)> = BTreeMap::new(); 
+    if agents.iter().all(|agent| agent.device.quality == 1. && agent.device.replacements == 0) {
+        return result; 
+    }
+    for agent in agents.iter() {
+        let entry = result.entry(agent.kind()).or_insert((0., 0)); 
+        entry.0 += agent.device.quality; 
+        entry.1 += agent.device.replacements; 
+    }
+    result
+}
+
+#[derive(Default)]
+pub struct Karma {
+    pub held: u32, 
+    pub minted: u32, 
+    pub spent: u32, 
+}
+
+// karma token flow per strategy type; types that never held a token are left out
+pub fn karma(agents: &[Agent]) -> BTreeMap<String, Karma> {
+    let mut result: BTreeMap<String, Karma> = BTreeMap::new(); 
+    for agent in agents.iter().filter(|agent| agent.karma > 0 || agent.karma_minted > 0) {
+        let entry = result.entry(agent.kind()).or_default(); 
+        entry.held += agent.karma; 
+        entry.minted += agent.karma_minted; 
+        entry.spent += agent.karma_spent; 
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*; 
+
+    fn correlation(samples: &[(f64, f64)]) -> Option<f64> {
+        let mut correlation = Correlation::default(); 
+        for (x, y) in samples {
+            correlation.add(*x, *y); 
+        }
+        correlation.value()
+    }
+
+    // the textbook two-pass formula
+    fn pearson(samples: &[(f64, f64)]) -> f64 {
+        let n = samples.len() as f64; 
+        let mean_x = samples.iter().map(|(x, _)| x).sum::<f64>() / n; 
+        let mean_y = samples.iter().map(|(_, y)| y).sum::<f64>() / n; 
+        let cov: f64 = samples.iter().map(|(x, y)| (x - mean_x) * (y - mean_y)).sum(); 
+        let var_x: f64 = samples.iter().map(|(x, _)| (x - mean_x).powi(2)).sum(); 
+        let var_y: f64 = samples.iter().map(|(_, y)| (y - mean_y).powi(2)).sum(); 
+        cov / (var_x * var_y).sqrt()
+    }
+
+    #[test]
+    fn matches_the_two_pass_formula() {
+        let samples = [(2., 0.9), (-1., 0.2), (0.5, 0.4), (3., 1.), (-4., 0.1), (1., 0.3)]; 
+        let streamed = correlation(&samples).unwrap(); 
+        assert!((streamed - pearson(&samples)).abs() < 1e-12); 
+    }
+
+    #[test]
+    fn perfectly_linear_samples_correlate_fully() {
+        let rising: Vec<(f64, f64)> = (0..10).map(|i| (i as f64, 0.1 * i as f64)).collect(); 
+        let falling: Vec<(f64, f64)> = (0..10).map(|i| (i as f64, 1. - 0.1 * i as f64)).collect(); 
+        assert!((correlation(&rising).unwrap() - 1.).abs() < 1e-12); 
+        assert!((correlation(&falling).unwrap() + 1.).abs() < 1e-12); 
+    }
+
+    #[test]
+    fn is_undefined_without_two_samples_or_variance() {
+        assert_eq!(correlation(&[]), None); 
+        assert_eq!(correlation(&[(1., 0.5)]), None); 
+        assert_eq!(correlation(&[(1., 0.2), (1., 0.8)]), None); 
+        assert_eq!(correlation(&[(1., 0.5), (2., 0.5)]), None); 
+    }
+}
@@ -0,0 +1,114 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::Agent;
+
+// Statistics of the directed positive-trust graph: an edge a -> b exists if a 
+// holds a reputation score above 0 for b, both being alive. 
+pub struct TrustNetwork {
+    pub edges: usize, 
+    // share of edges whose reverse edge exists as well
+    pub reciprocity: f64, 
+    // mean local clustering coefficient of the undirected graph, over nodes with 
+    // at least two neighbours
+    pub clustering: f64, 
+    // (min, median, max) over all living agents
+    pub in_degree: (usize, usize, usize), 
+    pub out_degree: (usize, usize, usize), 
+    // weakly connected components among agents with at least one edge
+    pub components: usize, 
+    pub isolated: usize, 
+}
+
+pub fn trust_network(agents: &[Agent]) -> TrustNetwork {
+    let alive: HashSet<usize> = agents.iter().map(|agent| agent.id).collect(); 
+    let mut out_edges: HashMap<usize, HashSet<usize>> = HashMap::new(); 
+    for agent in agents.iter() {
+        let targets = out_edges.entry(agent.id).or_default(); 
+        if let Some(reputations) = agent.strategy.reputations() {
+            for (partner, reputation) in reputations.iter() {
+                if *reputation > 0. && *partner != agent.id && alive.contains(partner) {
+                    targets.insert(*partner); 
+                }
+            }
+        }
+    }
+
+    let mut in_degree: HashMap<usize, usize> = alive.iter().map(|id| (*id, 0)).collect(); 
+    let mut neighbours: HashMap<usize, HashSet<usize>> = alive.iter().map(|id| (*id, HashSet::new())).collect(); 
+    let mut edges = 0; 
+    let mut reciprocated = 0; 
+    for (from, targets) in out_edges.iter() {
+        for to in targets.iter() {
+            edges += 1; 
+            *in_degree.get_mut(to).unwrap() += 1; 
+            if out_edges[to].contains(from) {
+                reciprocated += 1; 
+            }
+            neighbours.get_mut(from).unwrap().insert(*to); 
+            neighbours.get_mut(to).unwrap().insert(*from); 
+        }
+    }
+
+    let mut coefficients = vec![]; 
+    for adjacent in neighbours.values() {
+        if adjacent.len() < 2 {
+            continue; 
+        }
+        let adjacent: Vec<&usize> = adjacent.iter().collect(); 
+        let mut links = 0; 
+        for (i, a) in adjacent.iter().enumerate() {
+            for b in adjacent.iter().skip(i + 1) {
+                if neighbours[*a].contains(*b) {
+                    links += 1; 
+                }
+            }
+        }
+        let possible = adjacent.len() * (adjacent.len() - 1) / 2; 
+        coefficients.push(links as f64 / possible as f64); 
+    }
+
+    let mut components = 0; 
+    let mut isolated = 0; 
+    let mut visited: HashSet<usize> = HashSet::new(); 
+    for node in alive.iter() {
+        if visited.contains(node) {
+            continue; 
+        }
+        if neighbours[node].is_empty() {
+            isolated += 1; 
+            continue; 
+        }
+        components += 1; 
+        let mut stack = vec![*node]; 
+        visited.insert(*node); 
+        while let Some(current) = stack.pop() {
+            for next in neighbours[&current].iter() {
+                if visited.insert(*next) {
+                    stack.push(*next); 
+                }
+            }
+        }
+    }
+
+    TrustNetwork {
+        edges, 
+        reciprocity: if edges == 0 { 0. } else { reciprocated as f64 / edges as f64 }, 
+        clustering: if coefficients.is_empty() { 
+            0. 
+        } else { 
+            coefficients.iter().sum::<f64>() / coefficients.len() as f64 
+        }, 
+        in_degree: spread(in_degree.values().copied().collect()), 
+        out_degree: spread(out_edges.values().map(|targets| targets.len()).collect()), 
+        components, 
+        isolated, 
+    }
+}
+
+fn spread(mut values: Vec<usize>) -> (usize, usize, usize) {
+    if values.is_empty() {
+        return (0, 0, 0); 
+    }
+    values.sort_unstable(); 
+    (values[0], values[values.len() / 2], values[values.len() - 1])
+}
@@ -0,0 +1,72 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::process::{self, Child, ChildStdin, Command, Stdio};
+#[cfg(unix)]
+use std::os::unix::process::CommandExt;
+
+// An output file (report, regret log, csv), compressed while it is written if 
+// its name ends in `.gz` or `.zst`: the data is streamed through the system's 
+// `gzip` or `zstd`, which must then be installed. Anything else is plain. 
+pub enum OutputFile {
+    Plain(BufWriter<File>), 
+    Compressed { compressor: Child, input: Option<BufWriter<ChildStdin>> }, 
+}
+
+impl OutputFile {
+    pub fn create(path: &str) -> io::Result<OutputFile> {
+        let program: &[&str] = if path.ends_with(".gz") {
+            &["gzip", "-c"]
+        } else if path.ends_with(".zst") {
+            &["zstd", "-q", "-c"]
+        } else {
+            return Ok(OutputFile::Plain(BufWriter::new(File::create(path)?))); 
+        }; 
+        let mut command = Command::new(program[0]); 
+        command.args(&program[1..]).stdin(Stdio::piped()).stdout(File::create(path)?); 
+        // in a process group of its own, Ctrl-C doesn't reach the compressor, 
+        // which has to finish the file once the interrupted run is done
+        #[cfg(unix)]
+        command.process_group(0); 
+        let mut compressor = command.spawn()
+            .map_err(|e| io::Error::new(e.kind(), format!("cannot start {} to compress {}: {}", program[0], path, e)))?; 
+        let input = compressor.stdin.take().map(BufWriter::new); 
+        Ok(OutputFile::Compressed { compressor, input })
+    }
+}
+
+impl Write for OutputFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            OutputFile::Plain(file) => file.write(buf), 
+            OutputFile::Compressed { input, .. } => input.as_mut().expect("compressor input is open").write(buf), 
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            OutputFile::Plain(file) => file.flush(), 
+            OutputFile::Compressed { input, .. } => input.as_mut().expect("compressor input is open").flush(), 
+        }
+    }
+}
+
+// closing the compressor's input lets it write the end of the stream, which is 
+// waited for so that the file is complete when the run ends
+impl Drop for OutputFile {
+    fn drop(&mut self) {
+        if let OutputFile::Compressed { compressor, input } = self {
+            drop(input.take()); 
+            if let Err(e) = compressor.wait() {
+                eprintln!("error: compressor failed: {}", e); 
+            }
+        }
+    }
+}
+
+// ends the program if writing the output file at `path` failed
+pub fn exit_on_error(written: io::Result<()>, path: &str) {
+    if let Err(e) = written {
+        eprintln!("error: cannot write {}: {}", path, e); 
+        process::exit(1); 
+    }
+}
@@ -0,0 +1,84 @@
+use std::collections::VecDeque; 
+use std::io::{self, Write}; 
+use std::thread; 
+
+use crate::config::Config; 
+use crate::json::Json; 
+use crate::output::OutputFile; 
+use crate::{manifest, Agent}; 
+
+// The last `snapshots` rounds of a run (every agent's id, kind and energy, and 
+// optionally its inspected strategy state), kept in a ring buffer and written to 
+// the post-mortem file only when the run ends abnormally: on extinction, an 
+// interruption, an exceeded memory budget, a failed check, or a panic, which is 
+// caught while the buffer is dropped during unwinding. 
+// The first line holds the run manifest and why the run ended, then one JSON 
+// object per round. 
+pub struct PostMortem {
+    capacity: usize, 
+    with_state: bool, 
+    path: String, 
+    manifest: Vec<String>, 
+    // serialized snapshots, oldest first
+    rounds: VecDeque<String>, 
+    dumped: bool, 
+}
+
+impl PostMortem {
+    pub fn new(config: &Config) -> Option<PostMortem> {
+        (config.snapshots > 0).then(|| PostMortem {
+            capacity: config.snapshots, 
+            with_state: config.snapshot_state, 
+            path: config.snapshot_out.clone(), 
+            manifest: manifest::lines(config), 
+            rounds: VecDeque::with_capacity(config.snapshots), 
+            dumped: false, 
+        })
+    }
+
+    // `round` is the number of completed rounds
+    pub fn record(&mut self, round: i32, agents: &[Agent]) {
+        if self.rounds.len() == self.capacity {
+            self.rounds.pop_front(); 
+        }
+        let agents = agents.iter()
+            .map(|agent| {
+                let snapshot = Json::object([
+                    ("id", agent.id.into()), 
+                    ("kind", agent.kind().into()), 
+                    ("energy", agent.energy.into()), 
+                ]); 
+                if self.with_state { snapshot.with("state", agent.strategy.inspect()) } else { snapshot }
+            })
+            .collect(); 
+        let snapshot = Json::object([("round", Json::Number(round as f64)), ("agents", Json::Array(agents))]); 
+        self.rounds.push_back(snapshot.to_string()); 
+    }
+
+    // writes the buffered rounds, reporting the outcome on stderr
+    pub fn dump(&mut self, reason: &str) {
+        self.dumped = true; 
+        match self.write(reason) {
+            Ok(()) => eprintln!("post-mortem of the last {} rounds written to {}", self.rounds.len(), self.path), 
+            Err(e) => eprintln!("error: cannot write post-mortem {}: {}", self.path, e), 
+        }
+    }
+
+    fn write(&self, reason: &str) -> io::Result<()> {
+        let mut out = OutputFile::create(&self.path)?; 
+        let manifest = self.manifest.iter().map(|line| Json::from(line.as_str())).collect(); 
+        writeln!(out, "{}", Json::object([("manifest", Json::Array(manifest)), ("reason", reason.into())]))?; 
+        for round in self.rounds.iter() {
+            writeln!(out, "{}", round)?; 
+        }
+        out.flush()
+    }
+}
+
+impl Drop for PostMortem {
+    fn drop(&mut self) {
+        if thread::panicking() && !self.dumped {
+            self.dump("panic"); 
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use std::time::{Duration, Instant};
+
+// wall-clock time of one round, the part of it spent in the strategies' encounter 
+// decisions and notifications, and the encounters played
+struct RoundTiming {
+    wall: Duration, 
+    strategy: Duration, 
+    encounters: u64, 
+}
+
+// Per-round timing of a run, to see where custom strategies are slow. The 
+// encounters and the phases consulting strategies outside of them (transitive 
+// trust, witnesses, karma, signaling, gossip, imitation, discounting, forgiveness 
+// and the end of the round) are timed, everything else counts as engine 
+// bookkeeping. 
+pub struct Profile {
+    rounds: Vec<RoundTiming>, 
+    started: Instant, 
+    strategy: Duration, 
+    encounters: u64, 
+}
+
+impl Profile {
+    pub fn new() -> Profile {
+        Profile {
+            rounds: vec![], 
+            started: Instant::now(), 
+            strategy: Duration::ZERO, 
+            encounters: 0, 
+        }
+    }
+
+    pub fn start_round(&mut self) {
+        self.started = Instant::now(); 
+        self.strategy = Duration::ZERO; 
+        self.encounters = 0; 
+    }
+
+    // times one encounter
+    pub fn time<T>(&mut self, f: impl FnOnce() -> T) -> T {
+        self.encounters += 1; 
+        self.measure(f)
+    }
+
+    fn measure<T>(&mut self, f: impl FnOnce() -> T) -> T {
+        let start = Instant::now(); 
+        let result = f(); 
+        self.strategy += start.elapsed(); 
+        result
+    }
+
+    pub fn end_round(&mut self) {
+        self.rounds.push(RoundTiming {
+            wall: self.started.elapsed(), 
+            strategy: self.strategy, 
+            encounters: self.encounters, 
+        }); 
+    }
+
+    pub fn report(&self) {
+        let rate = |encounters: u64, wall: Duration| encounters as f64 / wall.as_secs_f64().max(1e-9); 
+        let share = |strategy: Duration, wall: Duration| strategy.as_secs_f64() / wall.as_secs_f64().max(1e-9); 
+        println!("profile:"); 
+        for (round, timing) in self.rounds.iter().enumerate() {
+            println!(
+                " - round {}: {:.3} ms, {} encounters ({:.0} per second), {:.1}% in strategy calls", 
+                round, 
+                timing.wall.as_secs_f64() * 1000., 
+                timing.encounters, 
+                rate(timing.encounters, timing.wall), 
+                share(timing.strategy, timing.wall) * 100.
+            ); 
+        }
+        let wall: Duration = self.rounds.iter().map(|timing| timing.wall).sum(); 
+        let strategy: Duration = self.rounds.iter().map(|timing| timing.strategy).sum(); 
+        let encounters: u64 = self.rounds.iter().map(|timing| timing.encounters).sum(); 
+        println!(
+            " - total: {:.3} s, {} encounters ({:.0} per second), {:.1}% in strategy calls, {:.1}% engine bookkeeping", 
+            wall.as_secs_f64(), 
+            encounters, 
+            rate(encounters, wall), 
+            share(strategy, wall) * 100., 
+            (1. - share(strategy, wall)) * 100.
+        ); 
+    }
+}
+
+// runs strategy calls outside of an encounter, timed if the run is profiled
+pub fn hooks<T>(profile: &mut Option<Profile>, f: impl FnOnce() -> T) -> T {
+    match profile.as_mut() {
+        Some(profile) => profile.measure(f), 
+        None => f(), 
+    }
+}
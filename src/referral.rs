@@ -0,0 +1,40 @@
+use std::collections::HashMap;
+
+use crate::{lend, pair_mut, Agent, GameParams, Outcome, REJECT};
+
+// Second chance for a rejected borrower: it names a voucher, the voucher may 
+// issue a referral and the lender may accept the request because of it. Both 
+// lender and voucher learn how the borrower behaved, so a voucher whose borrower 
+// defects can be held responsible. Returns the index of the voucher if a loan 
+// was made. 
+pub fn attempt(
+    agents: &mut [Agent], 
+    lender: usize, 
+    borrower: usize, 
+    index: &HashMap<usize, usize>, 
+    game: &GameParams
+) -> (Outcome, Option<usize>) {
+    let lender_id = agents[lender].id; 
+    let borrower_id = agents[borrower].id; 
+    let voucher_id = match agents[borrower].strategy.choose_voucher(lender_id) {
+        Some(voucher) => voucher, 
+        None => return (Outcome::Rejected, None)
+    };
+    let voucher = match index.get(&voucher_id) {
+        Some(voucher) if *voucher != lender && *voucher != borrower => *voucher, 
+        _ => return (Outcome::Rejected, None)
+    };
+    if !agents[voucher].strategy.issue_referral(borrower_id, lender_id) {
+        return (Outcome::Rejected, None); 
+    }
+    if agents[lender].strategy.evaluate_referral(borrower_id, voucher_id) == REJECT {
+        return (Outcome::Rejected, None); 
+    }
+
+    let (l, b) = pair_mut(agents, lender, borrower); 
+    let outcome = lend(l, b, game); 
+    let coop = outcome == Outcome::Cooperated; 
+    agents[lender].strategy.notify_referral_outcome(borrower_id, voucher_id, coop); 
+    agents[voucher].strategy.notify_vouchee_outcome(borrower_id, lender_id, coop); 
+    (outcome, Some(voucher))
+}
@@ -0,0 +1,113 @@
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, Write};
+
+use crate::config::Config;
+use crate::output::OutputFile;
+use crate::{manifest, Agent, GameParams, Outcome};
+
+// what an adaptive lender experienced with one particular borrower
+#[derive(Default)]
+struct PairRecord {
+    requests: u32, 
+    rejected: u32, 
+    cooperated: u32, 
+    // the lender's realized payoff from lending to this borrower
+    payoff: f64, 
+}
+
+// Tracks, for every adaptive lender, the regret of its accept/reject decisions 
+// versus the best fixed response in hindsight (always accept or always reject) 
+// against each borrower. The payoff of accepting a request that was actually 
+// rejected is estimated from the borrower's observed cooperation rate; the 
+// borrower role is not considered since cooperating is only a myopic loss. 
+pub struct RegretTracker {
+    pairs: HashMap<(usize, usize), PairRecord>, 
+    out: OutputFile, 
+}
+
+impl RegretTracker {
+    pub fn create(path: &str, config: &Config) -> io::Result<Self> {
+        let mut out = OutputFile::create(path)?; 
+        manifest::write(&mut out, config)?; 
+        writeln!(out, "round,strategy,agents,mean_regret,regret_per_request")?; 
+        Ok(Self {
+            pairs: HashMap::new(), 
+            out, 
+        })
+    }
+
+    pub fn record(&mut self, lender: &Agent, borrower: &Agent, outcome: Outcome, game: &GameParams) {
+        if !lender.strategy.is_adaptive() {
+            return; 
+        }
+        let pair = self.pairs.entry((lender.id, borrower.id)).or_default(); 
+        pair.requests += 1; 
+        match outcome {
+            Outcome::Rejected => pair.rejected += 1, 
+            Outcome::Cooperated => {
+                pair.cooperated += 1; 
+                pair.payoff += game.lender_coop_payout; 
+            }, 
+            Outcome::Defected => pair.payoff += game.lender_defect_payout, 
+        }
+    }
+
+    // forgets the decisions so far, regret is accumulated from now on
+    pub fn reset(&mut self) {
+        self.pairs.clear(); 
+    }
+
+    // appends one line per adaptive strategy type with the cumulative regret of its 
+    // living agents
+    pub fn log_round(&mut self, round: i32, agents: &[Agent], game: &GameParams) -> io::Result<()> {
+        let coop_rates: HashMap<usize, f64> = agents.iter()
+            .filter_map(|agent| agent.cooperation_rate().map(|rate| (agent.id, rate)))
+            .collect(); 
+        let adaptive: HashMap<usize, String> = agents.iter()
+            .filter(|agent| agent.strategy.is_adaptive())
+            .map(|agent| (agent.id, agent.kind()))
+            .collect(); 
+
+        // strategy type -> (agents, regret, requests)
+        let mut totals: BTreeMap<&String, (usize, f64, u32)> = BTreeMap::new(); 
+        for strategy in adaptive.values() {
+            totals.entry(strategy).or_insert((0, 0., 0)).0 += 1; 
+        }
+        for ((lender, borrower), pair) in self.pairs.iter() {
+            let strategy = match adaptive.get(lender) {
+                Some(strategy) => strategy, 
+                None => continue
+            };
+            let accepted = pair.requests - pair.rejected; 
+            let rate = match coop_rates.get(borrower) {
+                Some(rate) => Some(*rate), 
+                None if accepted > 0 => Some(pair.cooperated as f64 / accepted as f64), 
+                None => None
+            };
+            let always_accept = match rate {
+                Some(rate) => pair.payoff + pair.rejected as f64 * (
+                    rate * game.lender_coop_payout + (1. - rate) * game.lender_defect_payout
+                ), 
+                None => pair.payoff
+            };
+            let best_fixed = always_accept.max(0.); 
+            let entry = totals.get_mut(strategy).unwrap(); 
+            entry.1 += best_fixed - pair.payoff; 
+            entry.2 += pair.requests; 
+        }
+
+        for (strategy, (count, regret, requests)) in totals.iter() {
+            let per_request = if *requests == 0 { 0. } else { regret / *requests as f64 }; 
+            writeln!(
+                self.out, 
+                "{},{},{},{:.4},{:.6}", 
+                round, 
+                strategy, 
+                count, 
+                regret / *count as f64, 
+                per_request
+            )?; 
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,70 @@
+use std::io::{self, Write};
+
+use crate::config::{Config, Frequency, Sink};
+use crate::manifest;
+use crate::output::OutputFile;
+
+// Destination of the round report: stdout, a file or both. The file starts with 
+// the run manifest like every other output file. 
+pub struct Reporter {
+    frequency: Frequency, 
+    stdout: bool, 
+    file: Option<OutputFile>, 
+}
+
+impl Reporter {
+    pub fn create(config: &Config) -> io::Result<Self> {
+        let file = match (config.report_sink, &config.report_out) {
+            (Sink::Stdout, _) => None, 
+            (_, None) => return Err(io::Error::new(io::ErrorKind::InvalidInput, "the report sink needs --report-out")), 
+            (_, Some(path)) => {
+                let mut file = OutputFile::create(path)?; 
+                manifest::write(&mut file, config)?; 
+                Some(file)
+            }
+        }; 
+        Ok(Self {
+            frequency: config.report_every, 
+            stdout: !matches!(config.report_sink, Sink::File), 
+            file, 
+        })
+    }
+
+    // whether the state at the start of `round` is reported
+    pub fn due(&self, round: i32) -> bool {
+        match self.frequency {
+            Frequency::Every(n) => round % n == 0, 
+            Frequency::Final => false, 
+        }
+    }
+
+    pub fn set_frequency(&mut self, frequency: Frequency) {
+        self.frequency = frequency; 
+    }
+
+    pub fn final_only(&self) -> bool {
+        matches!(self.frequency, Frequency::Final)
+    }
+}
+
+impl Write for Reporter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.stdout {
+            io::stdout().write_all(buf)?; 
+        }
+        if let Some(file) = self.file.as_mut() {
+            file.write_all(buf)?; 
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if self.stdout {
+            io::stdout().flush()?; 
+        }
+        if let Some(file) = self.file.as_mut() {
+            file.flush()?; 
+        }
+        Ok(())
+    }
+}
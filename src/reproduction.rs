@@ -0,0 +1,51 @@
+use crate::config::Config;
+use crate::{seed, Agent};
+
+// births of the last round
+#[derive(Default)]
+pub struct Births {
+    pub born: usize, 
+    // offspring that didn't get a slot under the population cap
+    pub culled: usize, 
+}
+
+// Every agent with at least `reproduction_energy` energy gives birth to one 
+// offspring that inherits its strategy, including what the strategy learned, and 
+// half of its energy. With a population cap, births that would exceed it compete 
+// for the free slots: only the offspring of the fittest parents are born. 
+// Returns the indices of the new agents. 
+pub fn reproduce(agents: &mut Vec<Agent>, config: &Config, round: i32, next_id: &mut usize, births: &mut Births) -> Vec<usize> {
+    let threshold = match config.reproduction_energy {
+        Some(threshold) => threshold, 
+        None => return vec![]
+    };
+    let mut parents: Vec<usize> = (0..agents.len())
+        .filter(|i| agents[*i].energy >= threshold)
+        .collect(); 
+    let slots = config.population_cap.map_or(parents.len(), |cap| cap.saturating_sub(agents.len())); 
+    births.culled = parents.len().saturating_sub(slots); 
+    if births.culled > 0 {
+        parents.sort_by(|a, b| agents[*b].fitness(config).partial_cmp(&agents[*a].fitness(config)).unwrap()); 
+        parents.truncate(slots); 
+        parents.sort_unstable(); 
+    }
+    births.born = parents.len(); 
+
+    let mut offspring = vec![]; 
+    for parent in parents {
+        let id = *next_id; 
+        *next_id += 1; 
+        let mut strategy = agents[parent].strategy.clone(); 
+        strategy.reseed(seed::derive(config.seed, id as u64)); 
+        let mut child = Agent::new(id, strategy); 
+        agents[parent].energy /= 2.; 
+        child.energy = agents[parent].energy; 
+        child.discounted_energy = child.energy; 
+        child.accounted_energy = child.energy; 
+        child.arrived = Some(round); 
+        child.label = agents[parent].label.clone(); 
+        offspring.push(agents.len()); 
+        agents.push(child); 
+    }
+    offspring
+}
@@ -0,0 +1,136 @@
+use core::fmt;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use crate::config::Config;
+use crate::Agent;
+
+// how the winning strategy type of a run is determined
+#[derive(Clone, Copy)]
+pub enum Winner {
+    // highest weighted sum of the mean objectives
+    Weighted, 
+    // every type not dominated in all objectives
+    Pareto, 
+}
+
+impl FromStr for Winner {
+    type Err = (); 
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "weighted" => Ok(Winner::Weighted), 
+            "pareto" => Ok(Winner::Pareto), 
+            _ => Err(())
+        }
+    }
+}
+
+impl fmt::Display for Winner {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Winner::Weighted => "weighted", 
+            Winner::Pareto => "pareto", 
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// weights of the objectives, given as energy:w,shared:w,partners:w
+#[derive(Clone, Copy)]
+pub struct Weights {
+    pub energy: f64, 
+    pub shared: f64, 
+    pub partners: f64, 
+}
+
+impl FromStr for Weights {
+    type Err = (); 
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut weights = Weights { energy: 0., shared: 0., partners: 0. }; 
+        for part in s.split(',') {
+            let (name, weight) = part.split_once(':').ok_or(())?; 
+            let weight: f64 = weight.parse().map_err(|_| ())?; 
+            match name {
+                "energy" => weights.energy = weight, 
+                "shared" => weights.shared = weight, 
+                "partners" => weights.partners = weight, 
+                _ => return Err(())
+            }
+        }
+        Ok(weights)
+    }
+}
+
+impl fmt::Display for Weights {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "energy:{},shared:{},partners:{}", self.energy, self.shared, self.partners)
+    }
+}
+
+// mean objectives of the living agents of a strategy type
+struct Objectives {
+    energy: f64, 
+    // devices lent out and returned
+    shared: f64, 
+    // distinct borrowers that got a device
+    partners: f64, 
+}
+
+impl Objectives {
+    fn dominates(&self, other: &Objectives) -> bool {
+        self.energy >= other.energy && self.shared >= other.shared && self.partners >= other.partners 
+            && (self.energy > other.energy || self.shared > other.shared || self.partners > other.partners)
+    }
+}
+
+fn objectives(agents: &[Agent]) -> BTreeMap<String, Objectives> {
+    let mut sums: BTreeMap<String, (usize, f64, u32, usize)> = BTreeMap::new(); 
+    for agent in agents.iter() {
+        let entry = sums.entry(agent.kind()).or_insert((0, 0., 0, 0)); 
+        entry.0 += 1; 
+        entry.1 += agent.energy; 
+        entry.2 += agent.devices_shared; 
+        entry.3 += agent.partners_served.len(); 
+    }
+    sums.into_iter()
+        .map(|(st, (n, energy, shared, partners))| {
+            let n = n as f64; 
+            (st, Objectives { energy: energy / n, shared: shared as f64 / n, partners: partners as f64 / n })
+        })
+        .collect()
+}
+
+// Prints the objectives of every strategy type and the winner(s) of the run. 
+pub fn report(agents: &[Agent], config: &Config, winner: Winner) {
+    let objectives = objectives(agents); 
+    println!("objectives per living agent:"); 
+    for (st, o) in objectives.iter() {
+        println!(
+            " - {}: energy {:.2}, {:.1} devices shared, {:.1} partners served", 
+            st, 
+            o.energy, 
+            o.shared, 
+            o.partners
+        ); 
+    }
+    match winner {
+        Winner::Weighted => {
+            let w = &config.score_weights; 
+            let best = objectives.iter()
+                .map(|(st, o)| (st, w.energy * o.energy + w.shared * o.shared + w.partners * o.partners))
+                .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap()); 
+            if let Some((st, score)) = best {
+                println!("winner by weighted score ({}): {} with {:.2}", w, st, score); 
+            }
+        }
+        Winner::Pareto => {
+            let front: Vec<&str> = objectives.iter()
+                .filter(|(_, o)| !objectives.values().any(|other| other.dominates(o)))
+                .map(|(st, _)| st.as_str())
+                .collect(); 
+            println!("pareto front: {}", front.join(", ")); 
+        }
+    }
+}
@@ -0,0 +1,69 @@
+// Derives independent seeds for sub-streams (agents, generations, samples) from 
+// one base seed so that a whole run is reproducible from a single number. 
+// Uses the splitmix64 finalizer. 
+pub fn derive(seed: u64, stream: u64) -> u64 {
+    let mut z = seed ^ stream.wrapping_add(1).wrapping_mul(0x9e3779b97f4a7c15); 
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9); 
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb); 
+    z ^ (z >> 31)
+}
+
+// stream of the engine's own random decisions, far away from the agent streams
+pub const ENGINE: u64 = 1 << 63; 
+
+// stream of the spawn-time parameter jitter, split per agent
+pub const JITTER: u64 = ENGINE + 1; 
+
+// stream of the random draws of the encounters, split per round and pair
+pub const ENCOUNTER: u64 = ENGINE + 2; 
+
+// stream of the sample of agents whose state is dumped
+pub const DUMP: u64 = ENGINE + 3; 
+
+// stream of the random draws of one encounter, keyed on its round, participants and 
+// how often they met before in that round (weighted matchmaking and deferred 
+// requests can schedule a pair twice) instead of drawn in sequence, so it doesn't 
+// depend on the order of the encounters
+pub fn encounter(seed: u64, round: i32, lender: usize, borrower: usize, occurrence: u32) -> u64 {
+    let key = derive(derive(seed, ENCOUNTER), round as u64); 
+    derive(derive(derive(key, lender as u64), borrower as u64), occurrence as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet; 
+
+    use super::*; 
+
+    #[test]
+    fn encounter_keys_differ_in_every_component() {
+        let base = encounter(7, 3, 1, 2, 0); 
+        let variants = [
+            encounter(8, 3, 1, 2, 0), 
+            encounter(7, 4, 1, 2, 0), 
+            encounter(7, 3, 2, 2, 0), 
+            encounter(7, 3, 1, 3, 0), 
+            encounter(7, 3, 1, 2, 1), 
+            // the reversed roles are a different encounter
+            encounter(7, 3, 2, 1, 0), 
+        ]; 
+        for variant in variants {
+            assert_ne!(variant, base); 
+        }
+        assert_eq!(encounter(7, 3, 1, 2, 0), base); 
+    }
+
+    #[test]
+    fn encounter_keys_dont_collide_over_a_grid() {
+        let mut keys = HashSet::new(); 
+        for round in 0..10 {
+            for lender in 0..20 {
+                for borrower in 0..20 {
+                    for occurrence in 0..3 {
+                        assert!(keys.insert(encounter(1, round, lender, borrower, occurrence))); 
+                    }
+                }
+            }
+        }
+    }
+}
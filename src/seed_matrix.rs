@@ -0,0 +1,144 @@
+use std::collections::BTreeMap; 
+use std::fs; 
+use std::path::Path; 
+use std::process; 
+use std::sync::atomic::{AtomicUsize, Ordering}; 
+use std::sync::Mutex; 
+use std::thread; 
+
+use crate::config::Config; 
+use crate::seed_search::census; 
+use crate::{gen_agents, manifest, population, simulate}; 
+
+// what a run of the matrix ended with
+struct RunOutcome {
+    rounds: i32, 
+    // the type with the most living agents, None if all died
+    winner: Option<String>, 
+    // per initial type, the share of its agents alive at the end
+    survival: BTreeMap<String, f64>, 
+}
+
+// scenario files, with directories expanded to the files they contain, by name
+fn scenario_files(entries: &[String]) -> Vec<String> {
+    let mut files = vec![]; 
+    for entry in entries {
+        if !Path::new(entry).is_dir() {
+            files.push(entry.clone()); 
+            continue; 
+        }
+        let mut contained: Vec<String> = fs::read_dir(entry)
+            .unwrap_or_else(|e| {
+                eprintln!("error: cannot read {}: {}", entry, e); 
+                process::exit(1); 
+            })
+            .filter_map(|item| item.ok())
+            .map(|item| item.path())
+            .filter(|path| path.is_file())
+            .map(|path| path.to_string_lossy().into_owned())
+            .collect(); 
+        contained.sort(); 
+        files.extend(contained); 
+    }
+    files
+}
+
+fn run_one(config: &Config) -> RunOutcome {
+    let mut agents = gen_agents(population(config), &config.game, config.seed, config.jitter()); 
+    let initial = census(&agents); 
+    let summary = simulate(&mut agents, config); 
+    let alive = census(&agents); 
+    let winner = alive.iter()
+        .max_by_key(|(_, count)| **count)
+        .map(|(kind, _)| kind.clone()); 
+    let survival = initial.iter()
+        .map(|(kind, count)| (kind.clone(), *alive.get(kind).unwrap_or(&0) as f64 / *count as f64))
+        .collect(); 
+    RunOutcome { rounds: summary.rounds, winner, survival }
+}
+
+// Seed matrix: runs every scenario (applied on top of the given options) with 
+// every seed, on `threads` threads, and prints per scenario how often each type 
+// won (had the most living agents at the end) and the mean and standard 
+// deviation of each type's survival share over the seeds. Scenarios whose winner 
+// depends on the seed are flagged. 
+pub fn run(config: &Config) {
+    let scenarios = scenario_files(&config.matrix_scenarios); 
+    if scenarios.is_empty() || config.matrix_seeds.is_empty() {
+        eprintln!("error: the seed matrix needs --matrix-scenarios and --matrix-seeds"); 
+        process::exit(2); 
+    }
+    for line in manifest::lines(config) {
+        println!("# {}", line); 
+    }
+    let base = Config {
+        quiet: true, 
+        regret_out: None, 
+        dump_out: None, 
+        snapshots: 0, 
+        axelrod_summary: None, 
+        axelrod_matrix: None, 
+        network_stats: false, 
+        ..config.clone()
+    }; 
+    let mut configs = vec![]; 
+    for path in scenarios.iter() {
+        let mut scenario = base.clone(); 
+        if let Err(e) = scenario.load_scenario(path) {
+            eprintln!("error: {}", e); 
+            process::exit(2); 
+        }
+        for &seed in config.matrix_seeds.iter() {
+            configs.push(Config { seed, ..scenario.clone() }); 
+        }
+    }
+
+    // workers take the next run of the matrix until none is left
+    let next = AtomicUsize::new(0); 
+    let outcomes: Mutex<Vec<Option<RunOutcome>>> = Mutex::new((0..configs.len()).map(|_| None).collect()); 
+    thread::scope(|scope| {
+        for _ in 0..config.threads.clamp(1, configs.len()) {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::Relaxed); 
+                if i >= configs.len() {
+                    break; 
+                }
+                let outcome = run_one(&configs[i]); 
+                outcomes.lock().unwrap()[i] = Some(outcome); 
+            }); 
+        }
+    }); 
+    let outcomes: Vec<RunOutcome> = outcomes.into_inner().unwrap().into_iter().map(Option::unwrap).collect(); 
+
+    let seeds = config.matrix_seeds.len(); 
+    let mut flipping = 0; 
+    for (path, runs) in scenarios.iter().zip(outcomes.chunks(seeds)) {
+        let mut wins: BTreeMap<&str, usize> = BTreeMap::new(); 
+        for run in runs {
+            *wins.entry(run.winner.as_deref().unwrap_or("none")).or_insert(0) += 1; 
+        }
+        let flips = wins.len() > 1; 
+        flipping += flips as usize; 
+        let rounds = runs.iter().map(|run| run.rounds as f64).sum::<f64>() / seeds as f64; 
+        let tally: Vec<String> = wins.iter().map(|(kind, count)| format!("{} {}/{}", kind, count, seeds)).collect(); 
+        println!(
+            "Scenario {}: {:.1} rounds on average, winners: {}{}", 
+            path, 
+            rounds, 
+            tally.join(", "), 
+            if flips { " (outcome flips with the seed)" } else { "" }
+        ); 
+        let mut survival: BTreeMap<&str, Vec<f64>> = BTreeMap::new(); 
+        for run in runs {
+            for (kind, share) in run.survival.iter() {
+                survival.entry(kind.as_str()).or_default().push(*share); 
+            }
+        }
+        for (kind, shares) in survival {
+            let mean = shares.iter().sum::<f64>() / shares.len() as f64; 
+            let variance = shares.iter().map(|share| (share - mean).powi(2)).sum::<f64>() / shares.len() as f64; 
+            println!(" - {}: survival {:.3} ± {:.3}", kind, mean, variance.sqrt()); 
+        }
+    }
+    println!("{} of {} scenarios flip with the seed", flipping, scenarios.len()); 
+}
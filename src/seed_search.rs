@@ -0,0 +1,66 @@
+use std::collections::BTreeMap;
+use std::process;
+
+use crate::config::{Config, Predicate};
+use crate::{gen_agents, manifest, population, seed, simulate, Agent};
+
+// living agents per kind
+pub fn census(agents: &[Agent]) -> BTreeMap<String, usize> {
+    let mut counts = BTreeMap::new(); 
+    for agent in agents {
+        *counts.entry(agent.kind()).or_insert(0) += 1; 
+    }
+    counts
+}
+
+fn matches(predicate: &Predicate, rounds: i32, initial: &BTreeMap<String, usize>, alive: &BTreeMap<String, usize>) -> bool {
+    match predicate {
+        Predicate::Wins(kind) => alive.iter()
+            .max_by_key(|(_, count)| **count)
+            .is_some_and(|(winner, _)| winner.contains(kind.as_str())), 
+        Predicate::Extinct(kind) => initial.keys().any(|k| k.contains(kind.as_str())) 
+            && !alive.keys().any(|k| k.contains(kind.as_str())), 
+        Predicate::Coexistence(length) => rounds >= *length && alive.len() >= 2, 
+    }
+}
+
+// Seed search: runs the configured population with `search_seeds` seeds derived 
+// from the base seed and prints those whose outcome matches the predicate. The 
+// runs are quiet, so they are as cheap as the simulation itself. 
+pub fn run(config: &Config) {
+    let predicate = config.predicate.as_ref().unwrap_or_else(|| {
+        eprintln!("error: the seed search needs a --predicate"); 
+        process::exit(2); 
+    }); 
+    for line in manifest::lines(config) {
+        println!("# {}", line); 
+    }
+    let run_config = Config {
+        quiet: true, 
+        regret_out: None, 
+        dump_out: None, 
+        snapshots: 0, 
+        axelrod_summary: None, 
+        axelrod_matrix: None, 
+        network_stats: false, 
+        ..config.clone()
+    };
+    let mut found = 0; 
+    for s in 0..config.search_seeds {
+        let run_seed = seed::derive(config.seed, s as u64 + 1); 
+        let mut agents = gen_agents(population(config), &config.game, run_seed, config.jitter()); 
+        let initial = census(&agents); 
+        let summary = simulate(&mut agents, &Config { seed: run_seed, ..run_config.clone() }); 
+        let alive = census(&agents); 
+        if matches(predicate, summary.rounds, &initial, &alive) {
+            found += 1; 
+            let counts: Vec<String> = alive.iter().map(|(kind, count)| format!("{} {}", kind, count)).collect(); 
+            println!(
+                "Seed {}: {} rounds ({}), alive: {}", 
+                run_seed, summary.rounds, summary.reason, 
+                if counts.is_empty() { "none".to_string() } else { counts.join(", ") }, 
+            ); 
+        }
+    }
+    println!("{} of {} seeds match {}", found, config.search_seeds, predicate); 
+}
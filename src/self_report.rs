@@ -0,0 +1,34 @@
+use std::collections::HashMap;
+
+use crate::collusion::InteractionLog;
+use crate::{pair_mut, Agent, COOP};
+
+// Loans that are reported to the engine but never happened. The engine can't 
+// tell them from real ones: if the claimed borrower confirms, the lender is 
+// notified as if the borrower had returned the device. No device payoffs apply, 
+// only the reporting cost for both parties. 
+pub fn collect(agents: &mut [Agent], cost: f64, log: &mut InteractionLog) {
+    let index: HashMap<usize, usize> = agents.iter()
+        .enumerate()
+        .map(|(i, agent)| (agent.id, i))
+        .collect(); 
+    for l in 0..agents.len() {
+        let lender_id = agents[l].id; 
+        for borrower_id in agents[l].strategy.self_reported_encounters() {
+            let b = match index.get(&borrower_id) {
+                Some(b) if *b != l => *b, 
+                _ => continue
+            };
+            let (lender, borrower) = pair_mut(agents, l, b); 
+            if !borrower.strategy.confirm_self_report(lender_id) {
+                continue; 
+            }
+            lender.strategy.notify_coop_or_defect(borrower_id, COOP); 
+            lender.energy -= cost; 
+            borrower.energy -= cost; 
+            lender.self_reported += 1; 
+            log.record_praise(lender_id, borrower_id); 
+            borrower.self_reported += 1; 
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use crate::{Agent, Outcome};
+
+// Signaling phase before the lender decides: the borrower may send a promise of 
+// the given strength (0 to 1) that it will return the device. Signals are 
+// non-binding; by default they are cheap talk, otherwise the borrower pays `cost` 
+// times the strength, which makes strong signals partially credible. Returns 
+// the signal sent, if any. 
+pub fn exchange(agents: &mut [Agent], l: usize, b: usize, cost: f64) -> Option<f64> {
+    let lender_id = agents[l].id; 
+    let borrower_id = agents[b].id; 
+    let signal = agents[b].strategy.make_signal(lender_id, cost)?.clamp(0., 1.); 
+    agents[b].energy -= cost * signal; 
+    agents[b].signal_costs += cost * signal; 
+    agents[l].strategy.hear_signal(borrower_id, signal, cost); 
+    Some(signal)
+}
+
+// counts a stolen device after a promise as a broken promise
+pub fn settle(borrower: &mut Agent, signal: Option<f64>, outcome: Outcome) {
+    if outcome == Outcome::Defected && signal.is_some_and(|s| s > 0.) {
+        borrower.promises_broken += 1; 
+    }
+}
@@ -0,0 +1,53 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+
+static PAUSE: AtomicBool = AtomicBool::new(false); 
+static RESUME: AtomicBool = AtomicBool::new(false); 
+static INTERRUPT: AtomicBool = AtomicBool::new(false); 
+
+// exit status of a run that was interrupted with Ctrl-C, as shells report SIGINT
+pub const INTERRUPTED_STATUS: i32 = 130; 
+
+// Operator control of a running simulation: SIGUSR1 pauses it at the next round 
+// boundary, SIGUSR2 resumes it, SIGINT (Ctrl-C) ends it after the current round 
+// and a second SIGINT right away. Does nothing on platforms without these signals. 
+pub fn install() {
+    #[cfg(unix)]
+    unsafe {
+        let handler = on_signal as extern "C" fn(libc::c_int) as libc::sighandler_t; 
+        libc::signal(libc::SIGUSR1, handler); 
+        libc::signal(libc::SIGUSR2, handler); 
+        libc::signal(libc::SIGINT, handler); 
+    }
+}
+
+#[cfg(unix)]
+extern "C" fn on_signal(signal: libc::c_int) {
+    // only async-signal-safe work here: flip a flag
+    match signal {
+        libc::SIGUSR1 => PAUSE.store(true, Ordering::SeqCst), 
+        libc::SIGUSR2 => RESUME.store(true, Ordering::SeqCst), 
+        libc::SIGINT if INTERRUPT.swap(true, Ordering::SeqCst) => unsafe { libc::_exit(INTERRUPTED_STATUS) }, 
+        _ => {}
+    }
+}
+
+// whether the run was interrupted
+pub fn interrupted() -> bool {
+    INTERRUPT.load(Ordering::SeqCst)
+}
+
+// whether a pause was requested since the last call
+pub fn pause_requested() -> bool {
+    PAUSE.swap(false, Ordering::SeqCst)
+}
+
+// blocks until the resume signal arrives or the run is interrupted
+pub fn wait_for_resume() {
+    RESUME.store(false, Ordering::SeqCst); 
+    while !RESUME.swap(false, Ordering::SeqCst) && !interrupted() {
+        thread::sleep(Duration::from_millis(100)); 
+    }
+    PAUSE.store(false, Ordering::SeqCst); 
+}
@@ -0,0 +1,144 @@
+use core::fmt;
+use std::collections::{BTreeMap, VecDeque};
+
+use crate::config::{Config, FixationMeasure};
+use crate::Agent;
+
+pub enum StopReason {
+    RoundLimit, 
+    Extinction, 
+    // the dominating type, its share in the last round and the round its streak began
+    Fixation { strategy: String, share: f64, measure: FixationMeasure, since: i32 }, 
+    Stationary, 
+    MemoryBudget { used: u64 }, 
+    Interrupted, 
+}
+
+impl fmt::Display for StopReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StopReason::RoundLimit => write!(f, "round limit reached"), 
+            StopReason::Extinction => write!(f, "all agents died"), 
+            StopReason::Fixation { strategy, share, measure, since } => 
+                write!(f, "fixation of '{}' at {:.1}% of the {} since round {}", strategy, share * 100., measure, since), 
+            StopReason::Stationary => write!(f, "population is stationary"), 
+            StopReason::Interrupted => write!(f, "interrupted"), 
+            StopReason::MemoryBudget { used } => write!(f, "memory budget exceeded ({} MiB resident)", used), 
+        }
+    }
+}
+
+// strategy type -> (count, mean energy)
+type Snapshot = BTreeMap<String, (usize, f64)>;
+
+pub struct StopDetector {
+    fixation_share: Option<f64>, 
+    fixation_measure: FixationMeasure, 
+    fixation_rounds: usize, 
+    stationary_tolerance: Option<f64>, 
+    stationary_rounds: usize, 
+    // fixation and stationarity are only detected from this round on, after the warm-up
+    start: i32, 
+    // current leader and for how many consecutive rounds it exceeded the share
+    leader: Option<(String, usize)>, 
+    history: VecDeque<Snapshot>, 
+}
+
+impl StopDetector {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            fixation_share: config.fixation_share, 
+            fixation_measure: config.fixation_measure, 
+            fixation_rounds: config.fixation_rounds, 
+            stationary_tolerance: config.stationary_tolerance, 
+            stationary_rounds: config.stationary_rounds, 
+            start: config.warm_up, 
+            leader: None, 
+            history: VecDeque::new(), 
+        }
+    }
+
+    // call once after every round; returns a reason if the run should stop
+    pub fn observe(&mut self, agents: &[Agent], round: i32) -> Option<StopReason> {
+        if agents.is_empty() {
+            return Some(StopReason::Extinction); 
+        }
+        if round < self.start {
+            return None; 
+        }
+
+        let snapshot = snapshot(agents); 
+
+        if let Some(threshold) = self.fixation_share {
+            let (strategy, share) = match self.fixation_measure {
+                FixationMeasure::Population => {
+                    let (strategy, (count, _)) = snapshot.iter()
+                        .max_by_key(|(_, (count, _))| *count)
+                        .unwrap();
+                    (strategy, *count as f64 / agents.len() as f64)
+                }, 
+                FixationMeasure::Energy => {
+                    let total: f64 = snapshot.values().map(|(count, energy)| *count as f64 * energy).sum(); 
+                    let (strategy, (count, energy)) = snapshot.iter()
+                        .max_by(|(_, (a, x)), (_, (b, y))| (*a as f64 * x).total_cmp(&(*b as f64 * y)))
+                        .unwrap(); 
+                    // no share of a non-positive total
+                    (strategy, if total > 0. { *count as f64 * energy / total } else { 0. })
+                }
+            };
+            if share > threshold {
+                let streak = match &self.leader {
+                    Some((leader, streak)) if leader == strategy => streak + 1, 
+                    _ => 1
+                };
+                if streak >= self.fixation_rounds {
+                    let since = round + 1 - streak as i32; 
+                    return Some(StopReason::Fixation { strategy: strategy.clone(), share, measure: self.fixation_measure, since }); 
+                }
+                self.leader = Some((strategy.clone(), streak)); 
+            } else {
+                self.leader = None; 
+            }
+        }
+
+        if let Some(tolerance) = self.stationary_tolerance {
+            self.history.push_back(snapshot); 
+            if self.history.len() > self.stationary_rounds + 1 {
+                self.history.pop_front(); 
+            }
+            if self.history.len() == self.stationary_rounds + 1 && is_flat(&self.history, tolerance) {
+                return Some(StopReason::Stationary); 
+            }
+        }
+
+        None
+    }
+}
+
+fn snapshot(agents: &[Agent]) -> Snapshot {
+    let mut snapshot = Snapshot::new(); 
+    for agent in agents.iter() {
+        let entry = snapshot.entry(agent.strategy.get_type()).or_insert((0, 0.)); 
+        entry.0 += 1; 
+        entry.1 += agent.energy; 
+    }
+    for (count, energy) in snapshot.values_mut() {
+        *energy /= *count as f64; 
+    }
+    snapshot
+}
+
+fn is_flat(history: &VecDeque<Snapshot>, tolerance: f64) -> bool {
+    let first = &history[0]; 
+    history.iter().skip(1).all(|snapshot| {
+        snapshot.len() == first.len() && snapshot.iter().all(|(strategy, (count, energy))| {
+            match first.get(strategy) {
+                Some((first_count, first_energy)) => {
+                    count == first_count && 
+                        (energy - first_energy).abs() <= tolerance * first_energy.abs().max(1.)
+                }, 
+                None => false
+            }
+        })
+    })
+}
@@ -0,0 +1,116 @@
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::process;
+
+use crate::config::Config;
+use crate::output::{exit_on_error, OutputFile};
+use crate::stopping::StopReason;
+use crate::{gen_agents, manifest, population, seed, simulate};
+
+// Forgiveness sweep: runs the configured population `sweep_runs` times for each of 
+// `sweep_steps` evenly spaced forgiveness values from the configured range, all 
+// other settings fixed, and prints per value the mean cooperation level (share 
+// of loans whose device was returned), lending rate and tracker survival (share 
+// of the initial adaptive agents alive at the end). With a fixation share, also 
+// the share of runs that stopped on fixation, the mean round fixation began and 
+// the type that dominated most often. 
+pub fn run(config: &Config) {
+    for line in manifest::lines(config) {
+        println!("# {}", line); 
+    }
+    let run_config = Config {
+        quiet: true, 
+        regret_out: None, 
+        dump_out: None, 
+        snapshots: 0, 
+        axelrod_summary: None, 
+        axelrod_matrix: None, 
+        ..config.clone()
+    };
+    let mut out = config.sweep_out.as_ref().map(|path| {
+        let mut file = OutputFile::create(path).unwrap_or_else(|e| {
+            eprintln!("error: cannot write {}: {}", path, e); 
+            process::exit(1); 
+        });
+        let fixation = if config.fixation_share.is_some() { ",fixation_rate,fixation_round,dominant" } else { "" }; 
+        let header = manifest::write(&mut file, config)
+            .and_then(|_| writeln!(file, "forgiveness,cooperation,lending_rate,tracker_survival{}", fixation)); 
+        exit_on_error(header, path); 
+        (path, file)
+    });
+
+    let steps = config.sweep_steps.max(1); 
+    let runs = config.sweep_runs.max(1); 
+    let range = &config.forgiveness_range; 
+    for step in 0..steps {
+        let forgiveness = if steps == 1 {
+            range.min
+        } else {
+            range.min + (range.max - range.min) * step as f64 / (steps - 1) as f64
+        };
+        let (mut cooperation, mut lending, mut survival) = (0., 0., 0.); 
+        // type -> (fixated runs, sum of the rounds fixation began)
+        let mut fixations: BTreeMap<String, (usize, i64)> = BTreeMap::new(); 
+        for r in 0..runs {
+            // the same seeds for every forgiveness value
+            let run_seed = seed::derive(config.seed, r as u64 + 1); 
+            let mut agents = gen_agents(population(config), &config.game, run_seed, config.jitter()); 
+            let trackers = agents.iter().filter(|agent| agent.strategy.is_adaptive()).count(); 
+            let summary = simulate(&mut agents, &Config { forgiveness, seed: run_seed, ..run_config.clone() }); 
+            if let StopReason::Fixation { strategy, since, .. } = summary.reason {
+                let entry = fixations.entry(strategy).or_insert((0, 0)); 
+                entry.0 += 1; 
+                entry.1 += since as i64; 
+            }
+            if summary.loans > 0 {
+                cooperation += summary.cooperations as f64 / summary.loans as f64; 
+            }
+            if summary.requests > 0 {
+                lending += summary.loans as f64 / summary.requests as f64; 
+            }
+            if trackers > 0 {
+                let alive = agents.iter()
+                    .filter(|agent| agent.strategy.is_adaptive() && agent.arrived.is_none())
+                    .count(); 
+                survival += alive as f64 / trackers as f64; 
+            }
+        }
+        let n = runs as f64; 
+        let fixated: usize = fixations.values().map(|(count, _)| count).sum(); 
+        let began: i64 = fixations.values().map(|(_, since)| since).sum(); 
+        let mean_round = if fixated == 0 { f64::NAN } else { began as f64 / fixated as f64 }; 
+        let dominant = fixations.iter()
+            .max_by_key(|(_, (count, _))| *count)
+            .map_or("none", |(strategy, _)| strategy.as_str()); 
+        let fixation = config.fixation_share.map(|_| (fixated as f64 / n, mean_round, dominant)); 
+        print!(
+            "Forgiveness {:.3}: cooperation {:.3}, lending rate {:.3}, tracker survival {:.3}", 
+            forgiveness, 
+            cooperation / n, 
+            lending / n, 
+            survival / n
+        ); 
+        match fixation {
+            Some((rate, round, dominant)) => 
+                println!(", fixation {:.3} (from round {:.1}, mostly '{}')", rate, round, dominant), 
+            None => println!(), 
+        }
+        if let Some((path, file)) = out.as_mut() {
+            let row = write!(
+                file, 
+                "{:.4},{:.4},{:.4},{:.4}", 
+                forgiveness, 
+                cooperation / n, 
+                lending / n, 
+                survival / n
+            ).and_then(|_| match fixation {
+                Some((rate, round, dominant)) => writeln!(file, ",{:.4},{:.2},{}", rate, round, dominant), 
+                None => writeln!(file), 
+            }); 
+            exit_on_error(row, path); 
+        }
+    }
+    if let Some((path, file)) = out.as_mut() {
+        exit_on_error(file.flush(), path); 
+    }
+}
@@ -0,0 +1,72 @@
+use std::io::{self, IsTerminal, Write};
+use std::time::{Duration, Instant};
+
+use crate::config::{Command, Config, Frequency, OutputProfile, Sink};
+
+const BAR_WIDTH: usize = 30; 
+// the progress bar is redrawn at most this often
+const REDRAW: Duration = Duration::from_millis(100); 
+
+// Whether a person watches the run in a terminal, so that colors and a progress 
+// bar are welcome. `auto` decides by whether stdout and stderr are terminals; 
+// batch schedulers and pipes get plain output. 
+pub fn interactive(config: &Config) -> bool {
+    match config.output {
+        OutputProfile::Auto => io::stdout().is_terminal() && io::stderr().is_terminal(), 
+        OutputProfile::Headless => false, 
+        OutputProfile::Interactive => true, 
+    }
+}
+
+// `text` in bold and the given ANSI color (e.g. 32 green, 33 yellow) if interactive
+pub fn colored(text: &str, color: u8, interactive: bool) -> String {
+    if interactive {
+        format!("\x1b[1;{}m{}\x1b[0m", color, text)
+    } else {
+        text.to_string()
+    }
+}
+
+// Progress bar of a simulation on stderr. Only drawn when the round report doesn't 
+// go to the terminal, so the two don't get in each other's way. 
+pub struct Progress {
+    rounds: i32, 
+    drawn: Option<Instant>, 
+}
+
+impl Progress {
+    pub fn new(config: &Config) -> Option<Progress> {
+        let reports_to_terminal = !config.quiet 
+            && !matches!(config.report_sink, Sink::File) 
+            && !matches!(config.report_every, Frequency::Final); 
+        let simulation = matches!(config.command, Command::Simulate); 
+        (simulation && interactive(config) && !reports_to_terminal).then_some(Progress { rounds: config.rounds, drawn: None })
+    }
+
+    // `round` is the number of completed rounds
+    pub fn update(&mut self, round: i32, alive: usize) {
+        if self.drawn.is_some_and(|drawn| drawn.elapsed() < REDRAW) {
+            return; 
+        }
+        self.drawn = Some(Instant::now()); 
+        let done = (round as usize * BAR_WIDTH) / self.rounds.max(1) as usize; 
+        eprint!(
+            "\r\x1b[K[{}{}] round {}/{}, {} alive", 
+            "=".repeat(done), " ".repeat(BAR_WIDTH - done.min(BAR_WIDTH)), round, self.rounds, alive, 
+        ); 
+        let _ = io::stderr().flush(); 
+    }
+
+    // removes the bar, e.g. before other output on stderr
+    pub fn clear(&mut self) {
+        if self.drawn.take().is_some() {
+            eprint!("\r\x1b[K"); 
+        }
+    }
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        self.clear(); 
+    }
+}
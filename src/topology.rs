@@ -0,0 +1,164 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::config::TopologyKind;
+use crate::Agent;
+
+// Who may meet whom. Agents only lend to and borrow from their neighbors, so 
+// every agent has a number of encounters per round proportional to its degree. 
+pub struct Topology {
+    neighbors: BTreeMap<usize, BTreeSet<usize>>, 
+    // every edge end, each node appearing once per edge, for preferential attachment
+    ends: Vec<usize>, 
+    // edges per attaching node
+    attach: usize, 
+}
+
+impl Topology {
+    // `random`: every pair is connected with probability degree / (n - 1). 
+    // `scale-free`: preferential attachment, every node after the first 
+    // degree / 2 + 1 connects to degree / 2 existing nodes picked with probability 
+    // proportional to their degree. 
+    pub fn generate(kind: TopologyKind, ids: &[usize], degree: usize, rng: &mut StdRng) -> Topology {
+        let mut topology = Topology {
+            neighbors: ids.iter().map(|id| (*id, BTreeSet::new())).collect(), 
+            ends: vec![], 
+            attach: (degree / 2).max(1), 
+        };
+        match kind {
+            TopologyKind::Complete => {
+                for (i, a) in ids.iter().enumerate() {
+                    for b in ids[i + 1..].iter() {
+                        topology.connect(*a, *b); 
+                    }
+                }
+            }
+            TopologyKind::Random => {
+                let p = if ids.len() < 2 { 0. } else { (degree as f64 / (ids.len() - 1) as f64).min(1.) }; 
+                // skips geometrically distributed runs of absent edges (Batagelj and 
+                // Brandes), which visits only the edges, not all n² pairs
+                let (mut v, mut w) = (1, -1_i64); 
+                while p > 0. && v < ids.len() {
+                    let skip = if p < 1. { ((1. - rng.gen::<f64>()).ln() / (1. - p).ln()).floor() } else { 0. }; 
+                    w += 1 + skip.min(ids.len() as f64) as i64; 
+                    while w >= v as i64 && v < ids.len() {
+                        w -= v as i64; 
+                        v += 1; 
+                    }
+                    if v < ids.len() {
+                        topology.connect(ids[v], ids[w as usize]); 
+                    }
+                }
+            }
+            TopologyKind::ScaleFree => {
+                let seed = (topology.attach + 1).min(ids.len()); 
+                for (i, a) in ids[..seed].iter().enumerate() {
+                    for b in ids[i + 1..seed].iter() {
+                        topology.connect(*a, *b); 
+                    }
+                }
+                for id in ids[seed..].iter() {
+                    topology.attach_node(*id, rng); 
+                }
+            }
+        }
+        topology
+    }
+
+    fn connect(&mut self, a: usize, b: usize) {
+        if a != b && self.neighbors.entry(a).or_default().insert(b) {
+            self.neighbors.entry(b).or_default().insert(a); 
+            self.ends.push(a); 
+            self.ends.push(b); 
+        }
+    }
+
+    // connects a newcomer by preferential attachment
+    pub fn attach_node(&mut self, id: usize, rng: &mut StdRng) {
+        let mut targets = HashSet::new(); 
+        let candidates = self.neighbors.len(); 
+        while targets.len() < self.attach.min(candidates) {
+            let target = match self.ends.choose(rng) {
+                Some(target) => *target, 
+                None => *self.neighbors.keys().nth(rng.gen_range(0..candidates)).unwrap()
+            };
+            targets.insert(target); 
+        }
+        self.neighbors.entry(id).or_default(); 
+        let mut targets: Vec<usize> = targets.into_iter().collect(); 
+        targets.sort_unstable(); 
+        for target in targets {
+            self.connect(id, target); 
+        }
+    }
+
+    // moves a node to a new id, keeping its edges
+    pub fn rename(&mut self, old: usize, new: usize) {
+        let neighbors = self.neighbors.remove(&old).unwrap_or_default(); 
+        for neighbor in neighbors.iter() {
+            let set = self.neighbors.get_mut(neighbor).unwrap(); 
+            set.remove(&old); 
+            set.insert(new); 
+        }
+        self.neighbors.insert(new, neighbors); 
+        for end in self.ends.iter_mut().filter(|end| **end == old) {
+            *end = new; 
+        }
+    }
+
+    // index pairs (lower index first) of every edge between living agents
+    pub fn pairs(&self, agents: &[Agent], index: &HashMap<usize, usize>) -> Vec<(usize, usize)> {
+        let mut pairs = vec![]; 
+        for (alice, agent) in agents.iter().enumerate() {
+            for neighbor in self.neighbors[&agent.id].iter() {
+                if let Some(bob) = index.get(neighbor).filter(|bob| **bob > alice) {
+                    pairs.push((alice, *bob)); 
+                }
+            }
+        }
+        pairs
+    }
+
+    pub fn are_neighbors(&self, a: usize, b: usize) -> bool {
+        self.neighbors.get(&a).is_some_and(|n| n.contains(&b))
+    }
+}
+
+// Outcomes stratified by degree decile over every agent that was ever part of the 
+// topology, dead agents counting with energy 0. 
+pub fn report(topology: &Topology, agents: &[Agent]) {
+    let alive: HashMap<usize, &Agent> = agents.iter().map(|agent| (agent.id, agent)).collect(); 
+    let mut nodes: Vec<(usize, usize)> = topology.neighbors.iter()
+        .map(|(id, neighbors)| (neighbors.len(), *id))
+        .collect(); 
+    nodes.sort_unstable(); 
+    if nodes.is_empty() {
+        return; 
+    }
+    println!("outcomes by degree decile:"); 
+    for decile in 0..10 {
+        let group = &nodes[decile * nodes.len() / 10..(decile + 1) * nodes.len() / 10]; 
+        if group.is_empty() {
+            continue; 
+        }
+        let living: Vec<&Agent> = group.iter().filter_map(|(_, id)| alive.get(id).copied()).collect(); 
+        let energy: f64 = living.iter().map(|agent| agent.energy).sum(); 
+        let exchanges: u32 = living.iter().map(|agent| agent.exchanges).sum(); 
+        let lost: f64 = living.iter().map(|agent| agent.lost_to_defections).sum(); 
+        let per_living = |x: f64| if living.is_empty() { 0. } else { x / living.len() as f64 }; 
+        println!(
+            " - decile {} (degree {}-{}): {} agents, {:.3} alive, mean energy {:.2}, {:.1} exchanges and {:.1} lost to defections per living agent", 
+            decile + 1, 
+            group[0].0, 
+            group[group.len() - 1].0, 
+            group.len(), 
+            living.len() as f64 / group.len() as f64, 
+            energy / group.len() as f64, 
+            per_living(exchanges as f64), 
+            per_living(lost)
+        ); 
+    }
+}
@@ -0,0 +1,129 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::Agent;
+
+// The positive-trust graph of the population: an edge a -> b with weight w exists 
+// while a holds a reputation w > 0 for b. Rebuilt once per round, after the phases 
+// that change reputations in bulk (gossip, discounting, forgiveness, vouching), and 
+// kept up to date incrementally for the pairs involved in each encounter. 
+#[derive(Default)]
+pub struct TrustGraph {
+    edges: HashMap<usize, HashMap<usize, f64>>, 
+}
+
+impl TrustGraph {
+    // re-reads every reputation of the population
+    pub fn rebuild(&mut self, agents: &[Agent]) {
+        self.edges.clear(); 
+        for agent in agents.iter() {
+            let targets: HashMap<usize, f64> = agent.strategy.reputations()
+                .into_iter()
+                .flatten()
+                .filter(|(partner, r)| **r > 0. && **partner != agent.id)
+                .map(|(partner, r)| (*partner, *r))
+                .collect(); 
+            if !targets.is_empty() {
+                self.edges.insert(agent.id, targets); 
+            }
+        }
+    }
+
+    // re-reads the agent's reputation of one partner after it may have changed
+    pub fn refresh(&mut self, agent: &Agent, partner: usize) {
+        let reputation = agent.strategy.reputations().and_then(|r| r.get(&partner)); 
+        match reputation {
+            Some(r) if *r > 0. && partner != agent.id => {
+                self.edges.entry(agent.id).or_default().insert(partner, *r); 
+            }, 
+            _ => {
+                if let Some(targets) = self.edges.get_mut(&agent.id) {
+                    targets.remove(&partner); 
+                }
+            }
+        }
+    }
+
+    pub fn retain_alive(&mut self, alive: &HashSet<usize>) {
+        self.edges.retain(|from, _| alive.contains(from)); 
+        for targets in self.edges.values_mut() {
+            targets.retain(|to, _| alive.contains(to)); 
+        }
+    }
+
+    // Trust of `from` in `to` via paths of at most `depth` edges through agents 
+    // it trusts. A path is as strong as its weakest edge; the strongest path 
+    // counts. None if there is no such path. 
+    pub fn transitive_trust(&self, from: usize, to: usize, depth: usize) -> Option<f64> {
+        // strongest known path to every reached node
+        let mut best: HashMap<usize, f64> = HashMap::new(); 
+        let mut frontier: Vec<(usize, f64)> = vec![(from, f64::INFINITY)]; 
+        for _ in 0..depth {
+            let mut next: Vec<(usize, f64)> = vec![]; 
+            for (node, strength) in frontier.iter() {
+                let targets = match self.edges.get(node) {
+                    Some(targets) => targets, 
+                    None => continue
+                };
+                for (target, weight) in targets.iter() {
+                    if *target == from {
+                        continue; 
+                    }
+                    let path = strength.min(*weight); 
+                    if best.get(target).is_none_or(|known| path > *known) {
+                        best.insert(*target, path); 
+                        // paths can't continue through the subject itself
+                        if *target != to {
+                            next.push((*target, path)); 
+                        }
+                    }
+                }
+            }
+            frontier = next; 
+        }
+        best.get(&to).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*; 
+
+    fn graph(edges: &[(usize, usize, f64)]) -> TrustGraph {
+        let mut graph = TrustGraph::default(); 
+        for (from, to, weight) in edges {
+            graph.edges.entry(*from).or_default().insert(*to, *weight); 
+        }
+        graph
+    }
+
+    #[test]
+    fn a_path_is_as_strong_as_its_weakest_edge() {
+        let graph = graph(&[(0, 1, 0.8), (1, 2, 0.3)]); 
+        assert_eq!(graph.transitive_trust(0, 2, 2), Some(0.3)); 
+    }
+
+    #[test]
+    fn the_strongest_path_counts() {
+        let graph = graph(&[(0, 1, 0.2), (1, 3, 0.9), (0, 2, 0.6), (2, 3, 0.5)]); 
+        assert_eq!(graph.transitive_trust(0, 3, 2), Some(0.5)); 
+    }
+
+    #[test]
+    fn paths_longer_than_the_depth_are_ignored() {
+        let graph = graph(&[(0, 1, 1.), (1, 2, 1.), (2, 3, 1.)]); 
+        assert_eq!(graph.transitive_trust(0, 3, 2), None); 
+        assert_eq!(graph.transitive_trust(0, 3, 3), Some(1.)); 
+    }
+
+    #[test]
+    fn paths_dont_return_to_the_source() {
+        let graph = graph(&[(0, 1, 1.), (1, 0, 1.)]); 
+        assert_eq!(graph.transitive_trust(0, 0, 3), None); 
+    }
+
+    #[test]
+    fn no_path_without_a_trusted_first_hop() {
+        let graph = graph(&[(1, 2, 1.)]); 
+        assert_eq!(graph.transitive_trust(0, 2, 3), None); 
+    }
+}
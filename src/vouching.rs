@@ -0,0 +1,89 @@
+use std::collections::HashMap;
+
+use rand::rngs::StdRng;
+use rand::seq::index;
+
+use crate::config::Config;
+use crate::{Agent, Outcome};
+
+// energy a voucher put up for a newcomer, held by the engine until the 
+// newcomer's probation ends
+struct Stake {
+    voucher: usize, 
+    newcomer: usize, 
+    amount: f64, 
+    until: i32, 
+}
+
+// Vouching bootstrap for newcomers: a few established agents (older than the 
+// probation period) are asked in turn to sponsor a newcomer. The first one that 
+// agrees stakes energy and the vouch is announced to everyone, so agents that 
+// trust the voucher can extend some of that trust to the newcomer. If the newcomer 
+// defects during its probation, the stake goes to the lender as compensation and 
+// the lender is told who vouched for the defector; otherwise it is returned. 
+#[derive(Default)]
+pub struct Sponsorships {
+    stakes: Vec<Stake>, 
+}
+
+impl Sponsorships {
+    pub fn sponsor(&mut self, agents: &mut [Agent], newcomer: usize, round: i32, config: &Config, rng: &mut StdRng) {
+        let established: Vec<usize> = (0..agents.len())
+            .filter(|i| !agents[*i].on_probation(round, config))
+            .collect(); 
+        let count = config.sponsor_candidates.min(established.len()); 
+        let newcomer_id = agents[newcomer].id; 
+        for i in index::sample(rng, established.len(), count).iter() {
+            let v = established[i]; 
+            if agents[v].energy <= config.vouch_stake || !agents[v].strategy.sponsor_newcomer(newcomer_id) {
+                continue; 
+            }
+            let voucher_id = agents[v].id; 
+            agents[v].energy -= config.vouch_stake; 
+            self.stakes.push(Stake {
+                voucher: voucher_id, 
+                newcomer: newcomer_id, 
+                amount: config.vouch_stake, 
+                until: round + config.probation_rounds, 
+            }); 
+            for agent in agents.iter_mut() {
+                if agent.id != voucher_id && agent.id != newcomer_id {
+                    agent.strategy.hear_vouch(voucher_id, newcomer_id); 
+                }
+            }
+            return; 
+        }
+    }
+
+    // settles the stake of a sponsored borrower after one of its loans; returns the 
+    // index of the living voucher, which was told the outcome
+    pub fn on_loan(&mut self, agents: &mut [Agent], lender: usize, borrower: usize, outcome: Outcome, index: &HashMap<usize, usize>) -> Option<usize> {
+        let borrower_id = agents[borrower].id; 
+        let position = self.stakes.iter().position(|stake| stake.newcomer == borrower_id)?; 
+        let coop = outcome == Outcome::Cooperated; 
+        let voucher = self.stakes[position].voucher; 
+        agents[lender].strategy.notify_referral_outcome(borrower_id, voucher, coop); 
+        let v = index.get(&voucher).copied(); 
+        if let Some(v) = v {
+            let lender_id = agents[lender].id; 
+            agents[v].strategy.notify_vouchee_outcome(borrower_id, lender_id, coop); 
+        }
+        if !coop {
+            let stake = self.stakes.remove(position); 
+            agents[lender].energy += stake.amount; 
+        }
+        v
+    }
+
+    // returns the stakes of all newcomers whose probation is over
+    pub fn release(&mut self, agents: &mut [Agent], round: i32) {
+        let (expired, pending): (Vec<Stake>, Vec<Stake>) = self.stakes.drain(..)
+            .partition(|stake| stake.until <= round); 
+        self.stakes = pending; 
+        for stake in expired {
+            if let Some(voucher) = agents.iter_mut().find(|agent| agent.id == stake.voucher) {
+                voucher.energy += stake.amount; 
+            }
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use std::collections::HashMap;
+
+use crate::config::Config;
+use crate::topology::Topology;
+use crate::Agent;
+
+// Identity churn: agents whose strategy wants to shed its record leave under 
+// their old id and re-enter as newcomers, paying the entry fee and serving the 
+// probation again. Tracks what every fresh identity earned, to judge whether 
+// whitewashing pays under the configured entry frictions. 
+#[derive(Default)]
+pub struct Whitewashing {
+    changes: u32, 
+    fees: f64, 
+    // current id -> (energy before the change, round of the change)
+    fresh: HashMap<usize, (f64, i32)>, 
+    // energy gained and rounds lived over all finished identities
+    gained: f64, 
+    rounds: i32, 
+}
+
+impl Whitewashing {
+    pub fn churn(&mut self, agents: &mut [Agent], round: i32, next_id: &mut usize, topology: Option<&mut Topology>, config: &Config) {
+        let mut renamed = vec![]; 
+        for agent in agents.iter_mut() {
+            let age = round - agent.arrived.unwrap_or(0); 
+            if !agent.strategy.wants_new_identity(age) {
+                continue; 
+            }
+            if let Some((energy, since)) = self.fresh.remove(&agent.id) {
+                self.gained += agent.energy - energy; 
+                self.rounds += round - since; 
+            }
+            let id = *next_id; 
+            *next_id += 1; 
+            renamed.push((agent.id, id)); 
+            agent.id = id; 
+            agent.arrived = Some(round); 
+            // the fee counts against what the fresh identity gains
+            self.fresh.insert(id, (agent.energy, round)); 
+            agent.energy -= config.entry_fee; 
+            self.changes += 1; 
+            self.fees += config.entry_fee; 
+        }
+        if let Some(topology) = topology {
+            for (old, new) in renamed {
+                topology.rename(old, new); 
+            }
+        }
+    }
+
+    // forgets the identity changes so far; identities still in use count from now on
+    pub fn reset_statistics(&mut self, agents: &[Agent], round: i32) {
+        self.changes = 0; 
+        self.fees = 0.; 
+        self.gained = 0.; 
+        self.rounds = 0; 
+        let energy: HashMap<usize, f64> = agents.iter().map(|agent| (agent.id, agent.energy)).collect(); 
+        self.fresh.retain(|id, _| energy.contains_key(id)); 
+        for (id, fresh) in self.fresh.iter_mut() {
+            *fresh = (energy[id], round); 
+        }
+    }
+
+    pub fn report(&self, agents: &[Agent], rounds: i32) {
+        let (mut gained, mut lived) = (self.gained, self.rounds); 
+        let energy: HashMap<usize, f64> = agents.iter().map(|agent| (agent.id, agent.energy)).collect(); 
+        for (id, (start, since)) in self.fresh.iter() {
+            // dead identities end with energy 0
+            gained += energy.get(id).copied().unwrap_or(0.) - start; 
+            lived += rounds - since; 
+        }
+        println!(
+            "whitewashing: {} identity changes, {:.1} entry fees paid, fresh identities gained {:.2} energy per round", 
+            self.changes, 
+            self.fees, 
+            if lived == 0 { 0. } else { gained / lived as f64 }
+        ); 
+    }
+}
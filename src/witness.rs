@@ -0,0 +1,41 @@
+use rand::rngs::StdRng;
+use rand::seq::index;
+
+use crate::collusion::InteractionLog;
+use crate::Agent;
+
+// Asks up to `count` randomly chosen other agents what they think of the borrower 
+// and passes the answers on to the lender. Returns the number of agents queried, 
+// which the lender pays for whether they answered or not. False accusations are 
+// counted on the borrower. 
+pub fn query(
+    agents: &mut [Agent], 
+    lender: usize, 
+    borrower: usize, 
+    count: usize, 
+    log: &mut InteractionLog, 
+    rng: &mut StdRng
+) -> usize {
+    let candidates: Vec<usize> = (0..agents.len())
+        .filter(|i| *i != lender && *i != borrower)
+        .collect(); 
+    let count = count.min(candidates.len()); 
+    let subject = agents[borrower].id; 
+    let answers: Vec<(usize, f64)> = index::sample(rng, candidates.len(), count)
+        .iter()
+        .filter_map(|i| {
+            let witness = &agents[candidates[i]]; 
+            witness.strategy.asked_about(subject).map(|rating| (witness.id, rating))
+        })
+        .collect(); 
+    for (witness, rating) in answers {
+        agents[lender].strategy.hear_gossip(witness, subject, rating); 
+        if rating > 0. {
+            log.record_praise(witness, subject); 
+        }
+        if agents[borrower].is_slandered_by(rating) {
+            agents[borrower].false_negative_reports += 1; 
+        }
+    }
+    count
+}